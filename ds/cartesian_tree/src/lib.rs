@@ -0,0 +1,220 @@
+use std::ops::Range;
+
+use csr2::{CSRBuilder, Directed, Edge, CSR};
+
+/// 列の Cartesian tree。各部分木は連続する区間に対応し、その根は区間の最小値の位置である。
+/// 最小値が複数あるときは、最も左の位置を根とする。
+///
+/// # Example
+///
+/// ```
+/// use cartesian_tree::CartesianTree;
+///
+/// // 全ての部分列の最小値の総和
+/// let a = [3, 1, 2, 4, 1];
+/// let tree = CartesianTree::min(&a);
+/// let sum: usize = (0..a.len())
+///     .map(|i| {
+///         let range = tree.range(i);
+///         a[i] * (i - range.start + 1) * (range.end - i)
+///     })
+///     .sum();
+/// assert_eq!(sum, 22);
+///
+/// assert_eq!(tree.root(), Some(1));
+/// assert_eq!(tree.right(1), Some(4));
+/// assert_eq!(tree.left(4), Some(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CartesianTree {
+    root: Option<usize>,
+    parent: Box<[Option<usize>]>,
+    left: Box<[Option<usize>]>,
+    right: Box<[Option<usize>]>,
+    /// 各部分木が表す区間
+    ranges: Box<[Range<usize>]>,
+}
+
+impl CartesianTree {
+    /// 最小値を根とする Cartesian tree を構築する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn min<T: Ord>(values: &[T]) -> Self {
+        Self::with_comparator(values, |a, b| a < b)
+    }
+
+    /// 最大値を根とする Cartesian tree を構築する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn max<T: Ord>(values: &[T]) -> Self {
+        Self::with_comparator(values, |a, b| a > b)
+    }
+
+    /// 比較関数`less`の意味で最小の要素を根とする Cartesian tree を構築する。
+    /// `less(a, b)`は`a`が`b`より真に小さいときに限り`true`を返す必要がある（狭義弱順序）。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn with_comparator<T, F>(values: &[T], less: F) -> Self
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let n = values.len();
+        let mut parent = vec![None; n];
+        let mut left = vec![None; n];
+        let mut right = vec![None; n];
+        let mut ranges = vec![0..n; n];
+
+        // 右端のパスを管理する。等しい要素は取り出さないので、先に現れた方が祖先となる
+        let mut stack: Vec<usize> = Vec::with_capacity(n);
+        for (i, v) in values.iter().enumerate() {
+            let mut last = None;
+            while let Some(j) = stack.pop_if(|j| less(v, &values[*j])) {
+                ranges[j].end = i;
+                if let Some(k) = last {
+                    parent[k] = Some(j);
+                    right[j] = Some(k);
+                }
+                last = Some(j);
+            }
+            if let Some(k) = last {
+                parent[k] = Some(i);
+                left[i] = Some(k);
+            }
+            ranges[i].start = stack.last().map_or(0, |&j| j + 1);
+            stack.push(i);
+        }
+        for w in stack.windows(2) {
+            parent[w[1]] = Some(w[0]);
+            right[w[0]] = Some(w[1]);
+        }
+
+        Self {
+            root: stack.first().copied(),
+            parent: parent.into_boxed_slice(),
+            left: left.into_boxed_slice(),
+            right: right.into_boxed_slice(),
+            ranges: ranges.into_boxed_slice(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// 根を返す。列が空なら`None`を返す。
+    pub fn root(&self) -> Option<usize> {
+        self.root
+    }
+
+    /// `i`の親を返す。`i`が根なら`None`を返す。
+    pub fn parent(&self, i: usize) -> Option<usize> {
+        self.parent[i]
+    }
+
+    /// `i`の左の子を返す。
+    pub fn left(&self, i: usize) -> Option<usize> {
+        self.left[i]
+    }
+
+    /// `i`の右の子を返す。
+    pub fn right(&self, i: usize) -> Option<usize> {
+        self.right[i]
+    }
+
+    /// `i`を根とする部分木が表す区間を返す。
+    /// この区間は、`i`番目の要素を最小値（複数あれば最も左）とする極大な区間である。
+    pub fn range(&self, i: usize) -> Range<usize> {
+        self.ranges[i].clone()
+    }
+
+    /// 親から子への辺からなる有向グラフに変換する。各頂点の子は左、右の順に並ぶ。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn to_csr(&self) -> CSR<(), Directed> {
+        let n = self.len();
+        let mut builder = CSRBuilder::<(), Directed>::with_capacity(n.saturating_sub(1), n);
+        // 後から追加した辺ほど前に並ぶので、右の子から追加する
+        for source in 0..n {
+            for target in [self.right[source], self.left[source]]
+                .into_iter()
+                .flatten()
+            {
+                builder.push_edge(Edge {
+                    source,
+                    target,
+                    weight: (),
+                });
+            }
+        }
+
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    /// 区間`l..r`の Cartesian tree を素朴に構築し、根を返す。
+    fn naive(
+        values: &[u32],
+        l: usize,
+        r: usize,
+        parent: Option<usize>,
+        tree: &CartesianTree,
+    ) -> Option<usize> {
+        let root = (l..r).reduce(|i, j| if values[j] < values[i] { j } else { i })?;
+        assert_eq!(tree.parent(root), parent);
+        assert_eq!(tree.range(root), l..r);
+        assert_eq!(tree.left(root), naive(values, l, root, Some(root), tree));
+        assert_eq!(
+            tree.right(root),
+            naive(values, root + 1, r, Some(root), tree)
+        );
+
+        Some(root)
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = rand::rng();
+        for n in 0..100 {
+            for max in [2, 10, 1000] {
+                let values = Vec::from_iter((0..n).map(|_| rng.random_range(0..max)));
+                let tree = CartesianTree::min(&values);
+                assert_eq!(tree.len(), n);
+                assert_eq!(tree.root(), naive(&values, 0, n, None, &tree));
+
+                let rev = Vec::from_iter(values.iter().map(|v| max - v));
+                let max_tree = CartesianTree::max(&rev);
+                for i in 0..n {
+                    assert_eq!(max_tree.parent(i), tree.parent(i));
+                    assert_eq!(max_tree.range(i), tree.range(i));
+                }
+
+                let csr = tree.to_csr();
+                assert_eq!(csr.num_nodes(), n);
+                assert_eq!(csr.num_edges(), n.saturating_sub(1));
+                for i in 0..n {
+                    let children = Vec::from_iter(csr.out_edges(i).iter().map(|e| e.target));
+                    let expected = Vec::from_iter(tree.left(i).into_iter().chain(tree.right(i)));
+                    assert_eq!(children, expected);
+                }
+            }
+        }
+    }
+}
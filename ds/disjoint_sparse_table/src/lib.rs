@@ -4,6 +4,8 @@ use std::{fmt::Debug, ops::RangeBounds};
 
 /// 冪等性を仮定しない Sparse Table。
 /// `T: Group` なら差分計算の方が速い。
+///
+/// 非可換な演算でも、左から順に畳み込んだ結果を返す。
 #[derive(Debug, Clone)]
 pub struct DisjointSparseTable<T>
 where
@@ -92,7 +94,7 @@ where
 
             // 偶数個目の区間は右から、奇数個目の区間は左から累積和を計算をする
             for acc_r in value[i * len..].chunks_mut(width).step_by(2) {
-                let mut last = acc_r.last().unwrap().clone();
+                let mut last = *acc_r.last().unwrap();
                 for first in acc_r.iter_mut().rev().skip(1) {
                     *first = T::op(*first, last);
                     last = *first
@@ -131,7 +133,11 @@ where
 
 #[cfg(test)]
 mod tests {
-    use ops::ops::Additive;
+    use mint::Mint;
+    use ops::{
+        ops::{Additive, Affine},
+        Dual, Identity,
+    };
     use rand::Rng;
 
     use super::*;
@@ -169,4 +175,39 @@ mod tests {
             template(rng.random_range(1 << 5..1 << 10));
         }
     }
+
+    #[test]
+    fn non_commutative() {
+        type M = Mint<998244353>;
+
+        let mut rng = rand::rng();
+        for n in 0..100 {
+            let value =
+                Vec::from_iter((0..n).map(|_| (M::new(rng.random()), M::new(rng.random()))));
+            let dst = DisjointSparseTable::<Affine<M>>::from(value.clone());
+            let dual = DisjointSparseTable::<Dual<Affine<M>>>::from(value.clone());
+
+            for l in 0..n {
+                for r in l + 1..=n {
+                    assert_eq!(
+                        dst.range_query(l..r),
+                        Some(
+                            value[l..r]
+                                .iter()
+                                .fold(Affine::id(), |acc, f| Affine::op(acc, *f))
+                        )
+                    );
+                    assert_eq!(
+                        dual.range_query(l..r),
+                        Some(
+                            value[l..r]
+                                .iter()
+                                .rev()
+                                .fold(Affine::id(), |acc, f| Affine::op(acc, *f))
+                        )
+                    );
+                }
+            }
+        }
+    }
 }
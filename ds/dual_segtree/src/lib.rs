@@ -0,0 +1,264 @@
+use std::ops::{Bound, Range, RangeBounds};
+
+use ops::Monoid;
+
+/// 区間作用・一点取得を処理するデータ構造。
+///
+/// 各要素は作用素であり、`range_apply`で作用素を時系列順に合成する。
+/// つまり、`f`を適用した後に`g`を適用すると、要素`x`は`op(op(x, f), g)`となる。
+/// 非可換な演算でも、適用した順序を保つ。
+#[derive(Debug, Clone)]
+pub struct DualSegtree<T>
+where
+    T: Monoid,
+{
+    /// full binary tree. 配列は 1-origin とし、ルートは`data[1]`とする。
+    /// 内部ノードには子孫の葉に保留された作用素を持つ。`data.len() = 2 * offset`
+    data: Box<[T::Set]>,
+    offset: usize,
+    net_len: usize,
+}
+
+impl<T> DualSegtree<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    /// 単位元で初期化する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn new(n: usize) -> Self {
+        Self::from(vec![T::id(); n])
+    }
+
+    pub fn len(&self) -> usize {
+        self.net_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.net_len == 0
+    }
+
+    /// 適切にオフセットを追加する。
+    #[inline]
+    fn parse_range<R>(&self, range: R) -> Range<usize>
+    where
+        R: RangeBounds<usize>,
+    {
+        let l = match range.start_bound() {
+            Bound::Included(l) => l + self.offset,
+            Bound::Excluded(l) => l + self.offset + 1,
+            Bound::Unbounded => self.offset,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(r) => r + self.offset + 1,
+            Bound::Excluded(r) => r + self.offset,
+            Bound::Unbounded => self.offset + self.net_len,
+        };
+        assert!(r <= self.offset + self.net_len, "index out of bounds");
+
+        l..r
+    }
+
+    /// `i`番目のノードに保留された作用素を子ノードに伝搬する。
+    #[inline(always)]
+    fn propagate(&mut self, i: usize) {
+        let map = std::mem::replace(&mut self.data[i], T::id());
+        for c in [i << 1, (i << 1) | 1] {
+            self.data[c] = T::op(self.data[c], map)
+        }
+    }
+
+    /// 区間内の各要素に`f`を合成する。区間が空なら何もしない。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_apply<R>(&mut self, range: R, f: T::Set)
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range {
+            start: mut l,
+            end: mut r,
+        } = self.parse_range(range);
+        if l >= r {
+            return;
+        }
+
+        // 区間の境界を跨ぐノードの作用素を先に伝搬し、古い作用素が上に残らないようにする
+        for d in (1..=self.offset.trailing_zeros()).rev() {
+            if (l >> d) << d != l {
+                self.propagate(l >> d);
+            }
+            if (r >> d) << d != r {
+                self.propagate((r - 1) >> d);
+            }
+        }
+
+        while l < r {
+            if l & 1 == 1 {
+                self.data[l] = T::op(self.data[l], f);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                self.data[r] = T::op(self.data[r], f);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+    }
+
+    /// `i`番目の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_get(&self, i: usize) -> T::Set {
+        assert!(i < self.net_len, "index out of bounds");
+
+        // 祖先に保留された作用素ほど新しいので、下から順に合成する
+        let mut i = i + self.offset;
+        let mut res = self.data[i];
+        while i > 1 {
+            i >>= 1;
+            res = T::op(res, self.data[i]);
+        }
+
+        res
+    }
+
+    /// `i`番目の要素を`value`で置き換える。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_set(&mut self, i: usize, value: T::Set) {
+        assert!(i < self.net_len, "index out of bounds");
+
+        let i = i + self.offset;
+        for d in (1..=self.offset.trailing_zeros()).rev() {
+            self.propagate(i >> d);
+        }
+        self.data[i] = value;
+    }
+
+    /// 保留中の作用素をすべて葉に伝搬し、要素の列を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn materialize(&mut self) -> &[T::Set] {
+        for i in 1..self.offset {
+            self.propagate(i);
+        }
+
+        &self.data[self.offset..][..self.net_len]
+    }
+}
+
+impl<T> From<Vec<T::Set>> for DualSegtree<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    fn from(values: Vec<T::Set>) -> Self {
+        let net_len = values.len();
+        let offset = net_len.next_power_of_two();
+
+        let mut data = Vec::with_capacity(2 * offset);
+        data.extend(std::iter::repeat_n(T::id(), offset));
+        data.extend(values);
+        data.resize(2 * offset, T::id());
+
+        Self {
+            data: data.into_boxed_slice(),
+            offset,
+            net_len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mint::Mint;
+    use ops::{
+        ops::{Additive, Affine},
+        Identity, SemiGroup,
+    };
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn non_commutative() {
+        type M = Mint<998244353>;
+        type F = Affine<M>;
+
+        let mut rng = rand::rng();
+        for n in (0..20).chain([100, 1000]) {
+            let mut naive = vec![F::id(); n];
+            let mut segtree = DualSegtree::<F>::new(n);
+
+            for _ in 0..1000 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                let f = (M::new(rng.random()), M::new(rng.random()));
+                match rng.random_range(0..3) {
+                    0 => {
+                        naive[l..r].iter_mut().for_each(|g| *g = F::op(*g, f));
+                        segtree.range_apply(l..r, f);
+                    }
+                    1 if l < n => {
+                        naive[l] = f;
+                        segtree.point_set(l, f);
+                    }
+                    _ if l < n => assert_eq!(segtree.point_get(l), naive[l]),
+                    _ => {}
+                }
+            }
+
+            assert_eq!(segtree.materialize(), naive);
+            // 伝搬した後も同じ結果を返す
+            for (i, &f) in naive.iter().enumerate() {
+                assert_eq!(segtree.point_get(i), f);
+            }
+        }
+    }
+
+    #[test]
+    fn range_add() {
+        let mut rng = rand::rng();
+        for n in 1..50 {
+            let mut naive = Vec::from_iter((0..n).map(|_| rng.random_range(-100..100)));
+            let mut segtree = DualSegtree::<Additive<i64>>::from(naive.clone());
+
+            for _ in 0..200 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                let x = rng.random_range(-100..100);
+                naive[l..r].iter_mut().for_each(|v| *v += x);
+                segtree.range_apply(l..r, x);
+
+                let i = rng.random_range(0..n);
+                assert_eq!(segtree.point_get(i), naive[i]);
+            }
+        }
+    }
+}
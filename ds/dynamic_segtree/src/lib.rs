@@ -0,0 +1,997 @@
+use std::{
+    fmt::Debug,
+    ops::{Bound, Range, RangeBounds},
+};
+
+use ops::{Identity, Monoid, MonoidActionWithSize, SemiGroup};
+
+type Map<A> = <<A as MonoidActionWithSize>::Map as SemiGroup>::Set;
+type Arg<A> = <<A as MonoidActionWithSize>::Arg as SemiGroup>::Set;
+
+/// 番兵ノード。子が存在しないことを表し、単位元を保持する。
+const NIL: u32 = 0;
+const ROOT: u32 = 1;
+
+/// `[lo, hi)`を二等分する。オーバーフローしない。
+#[inline]
+fn split(lo: i64, hi: i64) -> i64 {
+    lo + (hi.abs_diff(lo) / 2) as i64
+}
+
+/// 区間幅。`i64`全体でもオーバーフローしない。
+#[inline]
+fn width(lo: i64, hi: i64) -> usize {
+    hi.abs_diff(lo) as usize
+}
+
+fn parse_range<R>(domain: &Range<i64>, range: R) -> Range<i64>
+where
+    R: RangeBounds<i64>,
+{
+    let l = match range.start_bound() {
+        Bound::Included(&l) => l,
+        Bound::Excluded(&l) => l + 1,
+        Bound::Unbounded => domain.start,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(&r) => r + 1,
+        Bound::Excluded(&r) => r,
+        Bound::Unbounded => domain.end,
+    };
+    assert!(domain.start <= l && r <= domain.end, "index out of bounds");
+
+    l..r
+}
+
+#[derive(Debug, Clone)]
+struct Node<S> {
+    value: S,
+    children: [u32; 2],
+}
+
+/// 添字の範囲が巨大な一点更新・区間クエリを処理するデータ構造。
+///
+/// 必要になったノードだけを確保するので、座標圧縮せずにオンラインで処理できる。
+/// 初期値はすべて単位元である。
+///
+/// 非可換な演算でも、左から順に畳み込んだ結果を返す。
+#[derive(Debug, Clone)]
+pub struct DynamicSegtree<T>
+where
+    T: Monoid,
+{
+    /// `nodes[0]`は番兵、`nodes[1]`はルートとする。
+    nodes: Vec<Node<T::Set>>,
+    domain: Range<i64>,
+}
+
+impl<T> DynamicSegtree<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    /// 添字の範囲が`domain`であるセグメント木を作る。
+    ///
+    /// # Panics
+    ///
+    /// - `domain` should not be empty
+    pub fn new(domain: Range<i64>) -> Self {
+        Self::with_capacity(domain, 0)
+    }
+
+    /// `capacity`個のノードを事前に確保する。
+    /// 一点更新ごとに高々 log *W* 個のノードが追加される。
+    ///
+    /// # Panics
+    ///
+    /// - `domain` should not be empty
+    pub fn with_capacity(domain: Range<i64>, capacity: usize) -> Self {
+        assert!(!domain.is_empty(), "domain should not be empty");
+
+        let mut nodes = Vec::with_capacity(capacity.max(2));
+        for _ in 0..2 {
+            nodes.push(Node {
+                value: T::id(),
+                children: [NIL; 2],
+            });
+        }
+
+        Self { nodes, domain }
+    }
+
+    /// `node`の子ノードを返す。存在しなければ作る。
+    fn child_or_insert(&mut self, node: u32, dir: usize) -> u32 {
+        if self.nodes[node as usize].children[dir] == NIL {
+            let child = u32::try_from(self.nodes.len()).expect("too many nodes");
+            self.nodes.push(Node {
+                value: T::id(),
+                children: [NIL; 2],
+            });
+            self.nodes[node as usize].children[dir] = child;
+        }
+
+        self.nodes[node as usize].children[dir]
+    }
+
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be in `domain`
+    pub fn point_update_with<F>(&mut self, i: i64, f: F)
+    where
+        F: FnOnce(T::Set) -> T::Set,
+    {
+        assert!(self.domain.contains(&i), "index out of bounds");
+
+        // 区間幅は 2^64 未満なので、深さは 64 以下
+        let mut path = [NIL; 64];
+        let mut depth = 0;
+        let mut node = ROOT;
+        let [mut lo, mut hi] = [self.domain.start, self.domain.end];
+        while lo + 1 < hi {
+            path[depth] = node;
+            depth += 1;
+
+            let mid = split(lo, hi);
+            let dir = (mid <= i) as usize;
+            if dir == 0 {
+                hi = mid
+            } else {
+                lo = mid
+            }
+            node = self.child_or_insert(node, dir);
+        }
+
+        let leaf = &mut self.nodes[node as usize].value;
+        *leaf = f(*leaf);
+
+        for &node in path[..depth].iter().rev() {
+            let [l, r] = self.nodes[node as usize].children;
+            self.nodes[node as usize].value =
+                T::op(self.nodes[l as usize].value, self.nodes[r as usize].value)
+        }
+    }
+
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be in `domain`
+    pub fn point_set(&mut self, i: i64, value: T::Set) {
+        self.point_update_with(i, |_| value);
+    }
+
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be in `domain`
+    pub fn point_get(&self, i: i64) -> T::Set {
+        assert!(self.domain.contains(&i), "index out of bounds");
+
+        let mut node = ROOT;
+        let [mut lo, mut hi] = [self.domain.start, self.domain.end];
+        while node != NIL && lo + 1 < hi {
+            let mid = split(lo, hi);
+            let dir = (mid <= i) as usize;
+            if dir == 0 {
+                hi = mid
+            } else {
+                lo = mid
+            }
+            node = self.nodes[node as usize].children[dir];
+        }
+
+        self.nodes[node as usize].value
+    }
+
+    fn fold(&self, node: u32, lo: i64, hi: i64, l: i64, r: i64) -> T::Set {
+        if node == NIL || r <= lo || hi <= l {
+            return T::id();
+        }
+        if l <= lo && hi <= r {
+            return self.nodes[node as usize].value;
+        }
+
+        let mid = split(lo, hi);
+        let [left, right] = self.nodes[node as usize].children;
+        T::op(
+            self.fold(left, lo, mid, l, r),
+            self.fold(right, mid, hi, l, r),
+        )
+    }
+
+    /// 区間クエリに答える。区間が空なら単位元を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_query<R>(&self, range: R) -> T::Set
+    where
+        R: RangeBounds<i64>,
+    {
+        let Range { start, end } = parse_range(&self.domain, range);
+        if start >= end {
+            return T::id();
+        }
+
+        self.fold(ROOT, self.domain.start, self.domain.end, start, end)
+    }
+
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn range_full_query(&self) -> T::Set {
+        self.nodes[ROOT as usize].value
+    }
+
+    fn max_right<F>(
+        &self,
+        node: u32,
+        [lo, hi]: [i64; 2],
+        l: i64,
+        pred: &mut F,
+        acc: &mut T::Set,
+    ) -> Option<i64>
+    where
+        F: FnMut(T::Set) -> bool,
+    {
+        // 番兵は単位元なので、`pred(acc)`と同じく`true`になる
+        if node == NIL || hi <= l {
+            return None;
+        }
+        if l <= lo {
+            let temp = T::op(*acc, self.nodes[node as usize].value);
+            if pred(temp) {
+                *acc = temp;
+                return None;
+            }
+            if lo + 1 == hi {
+                return Some(lo);
+            }
+        }
+
+        let mid = split(lo, hi);
+        let [left, right] = self.nodes[node as usize].children;
+        self.max_right(left, [lo, mid], l, pred, acc)
+            .or_else(|| self.max_right(right, [mid, hi], l, pred, acc))
+    }
+
+    /// `pred(l..r)`が`true`となる最大の`r`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// - `l` should be in `domain.start..=domain.end`
+    pub fn partition_right<F>(&self, l: i64, mut pred: F) -> i64
+    where
+        F: FnMut(T::Set) -> bool,
+    {
+        assert!(
+            (self.domain.start..=self.domain.end).contains(&l),
+            "index out of bounds"
+        );
+
+        let Range { start, end } = self.domain;
+        self.max_right(ROOT, [start, end], l, &mut pred, &mut T::id())
+            .unwrap_or(end)
+    }
+
+    fn min_left<F>(
+        &self,
+        node: u32,
+        [lo, hi]: [i64; 2],
+        r: i64,
+        pred: &mut F,
+        acc: &mut T::Set,
+    ) -> Option<i64>
+    where
+        F: FnMut(T::Set) -> bool,
+    {
+        if node == NIL || r <= lo {
+            return None;
+        }
+        if hi <= r {
+            let temp = T::op(self.nodes[node as usize].value, *acc);
+            if pred(temp) {
+                *acc = temp;
+                return None;
+            }
+            if lo + 1 == hi {
+                return Some(hi);
+            }
+        }
+
+        let mid = split(lo, hi);
+        let [left, right] = self.nodes[node as usize].children;
+        self.min_left(right, [mid, hi], r, pred, acc)
+            .or_else(|| self.min_left(left, [lo, mid], r, pred, acc))
+    }
+
+    /// `pred(l..r)`が`true`となる最小の`l`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// - `r` should be in `domain.start..=domain.end`
+    pub fn partition_left<F>(&self, r: i64, mut pred: F) -> i64
+    where
+        F: FnMut(T::Set) -> bool,
+    {
+        assert!(
+            (self.domain.start..=self.domain.end).contains(&r),
+            "index out of bounds"
+        );
+
+        let Range { start, end } = self.domain;
+        self.min_left(ROOT, [start, end], r, &mut pred, &mut T::id())
+            .unwrap_or(start)
+    }
+}
+
+struct LazyNode<F, X> {
+    value: X,
+    /// 子ノードに保留された作用素
+    map: F,
+    children: [u32; 2],
+}
+
+/// 添字の範囲が巨大な区間作用・区間クエリを処理するデータ構造。
+///
+/// 初期値はすべて単位元である。区間幅に依存する作用には、単位元が並んだ区間の幅が渡される。
+/// 作用素の合成順序などは[`ops::MonoidAction`]を参照すること。
+pub struct DynamicLazySegtree<A>
+where
+    A: MonoidActionWithSize<Map: Monoid<Set: Copy>, Arg: Monoid<Set: Copy>>,
+{
+    /// `nodes[0]`は番兵、`nodes[1]`はルートとする。
+    nodes: Vec<LazyNode<Map<A>, Arg<A>>>,
+    domain: Range<i64>,
+}
+
+impl<A> DynamicLazySegtree<A>
+where
+    A: MonoidActionWithSize<Map: Monoid<Set: Copy>, Arg: Monoid<Set: Copy>>,
+{
+    /// 添字の範囲が`domain`であるセグメント木を作る。
+    ///
+    /// # Panics
+    ///
+    /// - `domain` should not be empty
+    pub fn new(domain: Range<i64>) -> Self {
+        Self::with_capacity(domain, 0)
+    }
+
+    /// `capacity`個のノードを事前に確保する。
+    /// 更新ごとに *O*(log *W*) 個のノードが追加される。
+    ///
+    /// # Panics
+    ///
+    /// - `domain` should not be empty
+    pub fn with_capacity(domain: Range<i64>, capacity: usize) -> Self {
+        assert!(!domain.is_empty(), "domain should not be empty");
+
+        let mut nodes = Vec::with_capacity(capacity.max(2));
+        for _ in 0..2 {
+            nodes.push(LazyNode {
+                value: A::Arg::id(),
+                map: A::Map::id(),
+                children: [NIL; 2],
+            });
+        }
+
+        Self { nodes, domain }
+    }
+
+    /// 幅`size`のノードに作用素を作用させてから、保留中の作用素に合成する。
+    #[inline]
+    fn push(&mut self, node: u32, map: Map<A>, size: usize) {
+        let node = &mut self.nodes[node as usize];
+        node.value = A::act_with_size(map, node.value, size);
+        // 時系列順に作用素を合成
+        node.map = A::Map::op(node.map, map);
+    }
+
+    /// 保留された作用素を子ノードに伝搬する。子ノードが存在しなければ作る。
+    fn propagate(&mut self, node: u32, lo: i64, hi: i64) {
+        for dir in 0..2 {
+            if self.nodes[node as usize].children[dir] == NIL {
+                let child = u32::try_from(self.nodes.len()).expect("too many nodes");
+                self.nodes.push(LazyNode {
+                    value: A::Arg::id(),
+                    map: A::Map::id(),
+                    children: [NIL; 2],
+                });
+                self.nodes[node as usize].children[dir] = child;
+            }
+        }
+
+        let map = std::mem::replace(&mut self.nodes[node as usize].map, A::Map::id());
+        let mid = split(lo, hi);
+        let [left, right] = self.nodes[node as usize].children;
+        self.push(left, map, width(lo, mid));
+        self.push(right, map, width(mid, hi));
+    }
+
+    fn pull(&mut self, node: u32) {
+        let [left, right] = self.nodes[node as usize].children;
+        self.nodes[node as usize].value = A::Arg::op(
+            self.nodes[left as usize].value,
+            self.nodes[right as usize].value,
+        )
+    }
+
+    fn update(&mut self, node: u32, [lo, hi]: [i64; 2], [l, r]: [i64; 2], map: Map<A>) {
+        if r <= lo || hi <= l {
+            return;
+        }
+        if l <= lo && hi <= r {
+            self.push(node, map, width(lo, hi));
+            return;
+        }
+
+        self.propagate(node, lo, hi);
+        let mid = split(lo, hi);
+        let [left, right] = self.nodes[node as usize].children;
+        self.update(left, [lo, mid], [l, r], map);
+        self.update(right, [mid, hi], [l, r], map);
+        self.pull(node);
+    }
+
+    /// 区間内のデータを作用素で更新する。区間が空なら何もしない。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_update<R>(&mut self, range: R, map: Map<A>)
+    where
+        R: RangeBounds<i64>,
+    {
+        let Range { start, end } = parse_range(&self.domain, range);
+        if start >= end {
+            return;
+        }
+
+        let Range { start: lo, end: hi } = self.domain;
+        self.update(ROOT, [lo, hi], [start, end], map);
+    }
+
+    /// `i`番目の要素を`value`で置き換える。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be in `domain`
+    pub fn point_set(&mut self, i: i64, value: Arg<A>) {
+        assert!(self.domain.contains(&i), "index out of bounds");
+
+        // 区間幅は 2^64 未満なので、深さは 64 以下
+        let mut path = [NIL; 64];
+        let mut depth = 0;
+        let mut node = ROOT;
+        let [mut lo, mut hi] = [self.domain.start, self.domain.end];
+        while lo + 1 < hi {
+            path[depth] = node;
+            depth += 1;
+
+            self.propagate(node, lo, hi);
+            let mid = split(lo, hi);
+            let dir = (mid <= i) as usize;
+            if dir == 0 {
+                hi = mid
+            } else {
+                lo = mid
+            }
+            node = self.nodes[node as usize].children[dir];
+        }
+
+        self.nodes[node as usize].value = value;
+        for &node in path[..depth].iter().rev() {
+            self.pull(node);
+        }
+    }
+
+    /// `i`番目の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be in `domain`
+    pub fn point_get(&self, i: i64) -> Arg<A> {
+        assert!(self.domain.contains(&i), "index out of bounds");
+
+        // 祖先に保留された作用素ほど新しいので、下から順に作用させる
+        let mut maps = [A::Map::id(); 64];
+        let mut depth = 0;
+        let mut node = ROOT;
+        let [mut lo, mut hi] = [self.domain.start, self.domain.end];
+        while node != NIL && lo + 1 < hi {
+            maps[depth] = self.nodes[node as usize].map;
+            depth += 1;
+
+            let mid = split(lo, hi);
+            let dir = (mid <= i) as usize;
+            if dir == 0 {
+                hi = mid
+            } else {
+                lo = mid
+            }
+            node = self.nodes[node as usize].children[dir];
+        }
+
+        maps[..depth]
+            .iter()
+            .rev()
+            .fold(self.nodes[node as usize].value, |x, &f| {
+                A::act_with_size(f, x, 1)
+            })
+    }
+
+    /// 祖先に保留された作用素を合成した`map`を作用させて、区間クエリに答える。
+    fn fold(&self, node: u32, [lo, hi]: [i64; 2], [l, r]: [i64; 2], map: Map<A>) -> Arg<A> {
+        let [l, r] = [l.max(lo), r.min(hi)];
+        if l >= r {
+            return A::Arg::id();
+        }
+        if (l, r) == (lo, hi) {
+            return A::act_with_size(map, self.nodes[node as usize].value, width(lo, hi));
+        }
+
+        let mid = split(lo, hi);
+        let LazyNode {
+            map: pending,
+            children: [left, right],
+            ..
+        } = self.nodes[node as usize];
+        let map = A::Map::op(pending, map);
+        A::Arg::op(
+            self.fold(left, [lo, mid], [l, r], map),
+            self.fold(right, [mid, hi], [l, r], map),
+        )
+    }
+
+    /// 区間クエリに答える。区間が空なら単位元を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_query<R>(&self, range: R) -> Arg<A>
+    where
+        R: RangeBounds<i64>,
+    {
+        let Range { start, end } = parse_range(&self.domain, range);
+        let Range { start: lo, end: hi } = self.domain;
+
+        self.fold(ROOT, [lo, hi], [start, end], A::Map::id())
+    }
+
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn range_full_query(&self) -> Arg<A> {
+        self.nodes[ROOT as usize].value
+    }
+
+    fn max_right<F>(
+        &self,
+        node: u32,
+        [lo, hi]: [i64; 2],
+        l: i64,
+        map: Map<A>,
+        pred: &mut F,
+        acc: &mut Arg<A>,
+    ) -> Option<i64>
+    where
+        F: FnMut(Arg<A>) -> bool,
+    {
+        if hi <= l {
+            return None;
+        }
+        if l <= lo {
+            let value = A::act_with_size(map, self.nodes[node as usize].value, width(lo, hi));
+            let temp = A::Arg::op(*acc, value);
+            if pred(temp) {
+                *acc = temp;
+                return None;
+            }
+            if lo + 1 == hi {
+                return Some(lo);
+            }
+        }
+
+        let mid = split(lo, hi);
+        let LazyNode {
+            map: pending,
+            children: [left, right],
+            ..
+        } = self.nodes[node as usize];
+        let map = A::Map::op(pending, map);
+        self.max_right(left, [lo, mid], l, map, pred, acc)
+            .or_else(|| self.max_right(right, [mid, hi], l, map, pred, acc))
+    }
+
+    /// `pred(l..r)`が`true`となる最大の`r`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// - `l` should be in `domain.start..=domain.end`
+    pub fn partition_right<F>(&self, l: i64, mut pred: F) -> i64
+    where
+        F: FnMut(Arg<A>) -> bool,
+    {
+        assert!(
+            (self.domain.start..=self.domain.end).contains(&l),
+            "index out of bounds"
+        );
+
+        let Range { start, end } = self.domain;
+        self.max_right(
+            ROOT,
+            [start, end],
+            l,
+            A::Map::id(),
+            &mut pred,
+            &mut A::Arg::id(),
+        )
+        .unwrap_or(end)
+    }
+
+    fn min_left<F>(
+        &self,
+        node: u32,
+        [lo, hi]: [i64; 2],
+        r: i64,
+        map: Map<A>,
+        pred: &mut F,
+        acc: &mut Arg<A>,
+    ) -> Option<i64>
+    where
+        F: FnMut(Arg<A>) -> bool,
+    {
+        if r <= lo {
+            return None;
+        }
+        if hi <= r {
+            let value = A::act_with_size(map, self.nodes[node as usize].value, width(lo, hi));
+            let temp = A::Arg::op(value, *acc);
+            if pred(temp) {
+                *acc = temp;
+                return None;
+            }
+            if lo + 1 == hi {
+                return Some(hi);
+            }
+        }
+
+        let mid = split(lo, hi);
+        let LazyNode {
+            map: pending,
+            children: [left, right],
+            ..
+        } = self.nodes[node as usize];
+        let map = A::Map::op(pending, map);
+        self.min_left(right, [mid, hi], r, map, pred, acc)
+            .or_else(|| self.min_left(left, [lo, mid], r, map, pred, acc))
+    }
+
+    /// `pred(l..r)`が`true`となる最小の`l`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *W*)
+    ///
+    /// # Panics
+    ///
+    /// - `r` should be in `domain.start..=domain.end`
+    pub fn partition_left<F>(&self, r: i64, mut pred: F) -> i64
+    where
+        F: FnMut(Arg<A>) -> bool,
+    {
+        assert!(
+            (self.domain.start..=self.domain.end).contains(&r),
+            "index out of bounds"
+        );
+
+        let Range { start, end } = self.domain;
+        self.min_left(
+            ROOT,
+            [start, end],
+            r,
+            A::Map::id(),
+            &mut pred,
+            &mut A::Arg::id(),
+        )
+        .unwrap_or(start)
+    }
+}
+
+impl<A> Clone for DynamicLazySegtree<A>
+where
+    A: MonoidActionWithSize<Map: Monoid<Set: Copy>, Arg: Monoid<Set: Copy>>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            nodes: Vec::from_iter(self.nodes.iter().map(|node| LazyNode { ..*node })),
+            domain: self.domain.clone(),
+        }
+    }
+}
+
+impl<A> Debug for DynamicLazySegtree<A>
+where
+    A: MonoidActionWithSize<Map: Monoid<Set: Copy + Debug>, Arg: Monoid<Set: Copy + Debug>>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicLazySegtree")
+            .field("domain", &self.domain)
+            .field("nodes", &self.nodes.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use mint::Mint;
+    use ops::{
+        ops::{Additive, Affine, Max},
+        MonoidAction,
+    };
+    use rand::Rng;
+
+    use super::*;
+
+    /// 区間加算・区間最大値
+    struct AddMax;
+
+    impl MonoidAction for AddMax {
+        type Map = Additive<i64>;
+        type Arg = Max<i64>;
+
+        fn act(f: i64, x: i64) -> i64 {
+            if x == i64::MIN {
+                x
+            } else {
+                x + f
+            }
+        }
+    }
+
+    /// 区間加算・区間和
+    struct AddSum;
+
+    impl MonoidActionWithSize for AddSum {
+        type Map = Additive<i64>;
+        type Arg = Additive<i64>;
+
+        fn act_with_size(f: i64, x: i64, size: usize) -> i64 {
+            x + f * size as i64
+        }
+    }
+
+    #[test]
+    fn random() {
+        type M = Mint<998244353>;
+        type F = Affine<M>;
+
+        let mut rng = rand::rng();
+        for (lo, hi) in [(0, 1), (-3, 4), (-20, 13), (-100, 100)] {
+            let n = (hi - lo) as usize;
+            let mut naive = vec![F::id(); n];
+            let mut segtree = DynamicSegtree::<F>::new(lo..hi);
+            let mut additive = DynamicSegtree::<Additive<i64>>::new(lo..hi);
+            let mut sums = vec![0; n];
+
+            for _ in 0..1000 {
+                let l = rng.random_range(lo..=hi);
+                let r = rng.random_range(l..=hi);
+                let i = rng.random_range(lo..hi);
+                let [ul, ur, ui] = [l, r, i].map(|x| (x - lo) as usize);
+                match rng.random_range(0..4) {
+                    0 => {
+                        let f = (M::new(rng.random()), M::new(rng.random()));
+                        naive[ui] = f;
+                        segtree.point_set(i, f);
+
+                        let v = rng.random_range(0..100);
+                        sums[ui] += v;
+                        additive.point_update_with(i, |x| x + v);
+                    }
+                    1 => {
+                        assert_eq!(segtree.point_get(i), naive[ui]);
+                        assert_eq!(additive.point_get(i), sums[ui]);
+                    }
+                    2 => {
+                        let k = rng.random_range(0..100 * n as i64);
+                        assert_eq!(
+                            additive.partition_right(l, |v| v <= k),
+                            (ul..n)
+                                .find(|&j| sums[ul..=j].iter().sum::<i64>() > k)
+                                .map_or(hi, |j| j as i64 + lo)
+                        );
+                        assert_eq!(
+                            additive.partition_left(r, |v| v <= k),
+                            (0..ur)
+                                .rfind(|&j| sums[j..ur].iter().sum::<i64>() > k)
+                                .map_or(lo, |j| j as i64 + 1 + lo)
+                        );
+                    }
+                    _ => {
+                        assert_eq!(
+                            segtree.range_query(l..r),
+                            naive[ul..ur].iter().fold(F::id(), |acc, &f| F::op(acc, f))
+                        );
+                        assert_eq!(additive.range_query(l..r), sums[ul..ur].iter().sum::<i64>());
+                    }
+                }
+            }
+
+            assert_eq!(segtree.range_query(..), segtree.range_full_query());
+        }
+    }
+
+    #[test]
+    fn huge_domain() {
+        let mut rng = rand::rng();
+        let mut naive = BTreeMap::new();
+        let mut segtree = DynamicSegtree::<Max<i64>>::new(i64::MIN..i64::MAX);
+
+        for _ in 0..1000 {
+            let i = rng.random_range(i64::MIN..i64::MAX);
+            let v = rng.random_range(0..1000);
+            naive.insert(i, v);
+            segtree.point_set(i, v);
+
+            let l = rng.random_range(i64::MIN..i64::MAX);
+            let r = rng.random_range(l..i64::MAX);
+            assert_eq!(
+                segtree.range_query(l..r),
+                naive.range(l..r).map(|(_, &v)| v).max().unwrap_or(i64::MIN)
+            );
+            assert_eq!(
+                segtree.partition_right(l, |v| v < 500),
+                naive
+                    .range(l..)
+                    .find(|(_, &v)| v >= 500)
+                    .map_or(i64::MAX, |(&i, _)| i)
+            );
+        }
+        assert!(segtree.nodes.len() <= 1000 * 64 + 2);
+    }
+
+    #[test]
+    fn lazy_random() {
+        let mut rng = rand::rng();
+        for (lo, hi) in [(0, 1), (-3, 4), (-20, 13), (-100, 100)] {
+            let n = (hi - lo) as usize;
+            // 単位元が並んだ区間への作用を確認するため、最大値は -inf ではなく 0 で初期化する
+            let mut max = DynamicLazySegtree::<AddMax>::new(lo..hi);
+            max.range_update(.., 0);
+            let mut naive_max = vec![i64::MIN; n];
+            let mut sum = DynamicLazySegtree::<AddSum>::new(lo..hi);
+            let mut naive = vec![0; n];
+            for i in lo..hi {
+                max.point_set(i, i64::MIN);
+            }
+
+            for _ in 0..1000 {
+                let l = rng.random_range(lo..=hi);
+                let r = rng.random_range(l..=hi);
+                let i = rng.random_range(lo..hi);
+                let [ul, ur, ui] = [l, r, i].map(|x| (x - lo) as usize);
+                match rng.random_range(0..4) {
+                    0 => {
+                        let f = rng.random_range(-100..100);
+                        naive[ul..ur].iter_mut().for_each(|v| *v += f);
+                        naive_max[ul..ur]
+                            .iter_mut()
+                            .filter(|v| **v != i64::MIN)
+                            .for_each(|v| *v += f);
+                        max.range_update(l..r, f);
+                        sum.range_update(l..r, f);
+                    }
+                    1 => {
+                        assert_eq!(max.point_get(i), naive_max[ui]);
+                        assert_eq!(sum.point_get(i), naive[ui]);
+
+                        let v = rng.random_range(-100..100);
+                        naive_max[ui] = v;
+                        naive[ui] = v;
+                        max.point_set(i, v);
+                        sum.point_set(i, v);
+                    }
+                    2 => {
+                        let k = rng.random_range(-200..200);
+                        let pred = |v: i64| v < k;
+                        assert_eq!(
+                            max.partition_right(l, pred),
+                            (ul..n)
+                                .find(|&j| naive_max[j] >= k)
+                                .map_or(hi, |j| j as i64 + lo)
+                        );
+                        assert_eq!(
+                            max.partition_left(r, pred),
+                            (0..ur)
+                                .rfind(|&j| naive_max[j] >= k)
+                                .map_or(lo, |j| j as i64 + 1 + lo)
+                        );
+                    }
+                    _ => {
+                        assert_eq!(
+                            max.range_query(l..r),
+                            naive_max[ul..ur].iter().copied().max().unwrap_or(i64::MIN)
+                        );
+                        assert_eq!(sum.range_query(l..r), naive[ul..ur].iter().sum::<i64>());
+                    }
+                }
+            }
+
+            assert_eq!(sum.range_full_query(), naive.iter().sum::<i64>());
+        }
+    }
+
+    #[test]
+    fn lazy_huge_domain() {
+        const W: i64 = 1_000_000_000_000;
+
+        let mut rng = rand::rng();
+        let mut updates = vec![];
+        let mut sum = DynamicLazySegtree::<AddSum>::new(-W..W);
+        for _ in 0..200 {
+            let l = rng.random_range(-W..=W);
+            let r = rng.random_range(l..=W);
+            let f = rng.random_range(-100..100);
+            updates.push((l, r, f));
+            sum.range_update(l..r, f);
+
+            let l = rng.random_range(-W..=W);
+            let r = rng.random_range(l..=W);
+            assert_eq!(
+                sum.range_query(l..r),
+                updates
+                    .iter()
+                    .map(|&(ql, qr, f)| f * (qr.min(r) - ql.max(l)).max(0))
+                    .sum::<i64>()
+            );
+        }
+    }
+}
@@ -180,3 +180,233 @@ where
         Self::from(Vec::from_iter(iter))
     }
 }
+
+/// 可換群の値の列に対して、区間加算・区間和を高速に計算するデータ構造。
+///
+/// `i`番目までの累積値を`i * s1(i) - s2(i)`と表し、`s1`と`s2`をそれぞれ Fenwick tree で管理する。
+///
+/// # Example
+///
+/// ```
+/// use fenwick::RangeAddFenwickTree;
+/// use ops::ops::Additive;
+///
+/// let mut tree = RangeAddFenwickTree::<Additive<i64>>::from(vec![1, 2, 3, 4, 5]);
+/// tree.range_add(1..4, 10);
+/// assert_eq!(tree.range_sum(..), 45);
+/// assert_eq!(tree.range_sum(3..), 19);
+/// ```
+pub struct RangeAddFenwickTree<T: SemiGroup + Commutative> {
+    s1: FenwickTree<T>,
+    s2: FenwickTree<T>,
+}
+
+impl<T> Clone for RangeAddFenwickTree<T>
+where
+    T: SemiGroup + Commutative,
+    FenwickTree<T>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            s1: self.s1.clone(),
+            s2: self.s2.clone(),
+        }
+    }
+}
+
+impl<T> Debug for RangeAddFenwickTree<T>
+where
+    T: SemiGroup + Commutative,
+    FenwickTree<T>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RangeAddFenwickTree")
+            .field("s1", &self.s1)
+            .field("s2", &self.s2)
+            .finish()
+    }
+}
+
+impl<T> RangeAddFenwickTree<T>
+where
+    T: Group + Commutative,
+    T::Set: Copy,
+{
+    /// 単位元で初期化する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(*N*)
+    pub fn new(n: usize) -> Self {
+        Self {
+            s1: FenwickTree::new(n),
+            s2: FenwickTree::new(n),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.s1.lefts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.s1.lefts.is_empty()
+    }
+
+    /// `i`番目から末尾までの要素に`x`を加算する。`i = N`なら何もしない。
+    #[inline]
+    fn suffix_add(&mut self, i: usize, x: T::Set) {
+        if i < self.len() {
+            self.s1.point_update(i, x);
+            self.s2.point_update(i, pow::<T>(x, i));
+        }
+    }
+
+    /// 区間内の各要素に`x`を加算する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_add<R>(&mut self, range: R, x: T::Set)
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = parse_range(range, self.len());
+        if l < r {
+            self.suffix_add(l, x);
+            self.suffix_add(r, T::inv(x));
+        }
+    }
+
+    /// `0..n`番目の要素の和を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn prefix_sum(&self, n: usize) -> T::Set {
+        T::op(
+            pow::<T>(self.s1.prefix_query(n), n),
+            T::inv(self.s2.prefix_query(n)),
+        )
+    }
+
+    /// 区間内の要素の和を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_sum<R>(&self, range: R) -> T::Set
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = parse_range(range, self.len());
+        if l >= r {
+            return T::id();
+        }
+
+        T::op(self.prefix_sum(r), T::inv(self.prefix_sum(l)))
+    }
+}
+
+impl<T> From<Vec<T::Set>> for RangeAddFenwickTree<T>
+where
+    T: Group + Commutative,
+    T::Set: Copy,
+{
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    fn from(value: Vec<T::Set>) -> Self {
+        // 初期値は`-s2`に持たせる
+        Self {
+            s1: FenwickTree::new(value.len()),
+            s2: FenwickTree::from_iter(value.into_iter().map(T::inv)),
+        }
+    }
+}
+
+/// `x`を`n`個合成する。
+///
+/// # Time Complexity
+///
+/// *Θ*(log *n*)
+#[inline]
+fn pow<T>(mut x: T::Set, mut n: usize) -> T::Set
+where
+    T: Monoid,
+    T::Set: Copy,
+{
+    let mut res = T::id();
+    while n > 0 {
+        if n & 1 == 1 {
+            res = T::op(res, x);
+        }
+        x = T::op(x, x);
+        n >>= 1
+    }
+
+    res
+}
+
+#[inline]
+fn parse_range<R>(range: R, len: usize) -> (usize, usize)
+where
+    R: RangeBounds<usize>,
+{
+    let l = match range.start_bound() {
+        std::ops::Bound::Included(l) => *l,
+        std::ops::Bound::Excluded(l) => l + 1,
+        std::ops::Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        std::ops::Bound::Included(r) => r + 1,
+        std::ops::Bound::Excluded(r) => *r,
+        std::ops::Bound::Unbounded => len,
+    };
+    assert!(r <= len, "index out of bounds");
+
+    (l, r)
+}
+
+#[cfg(test)]
+mod tests {
+    use mint::Mint;
+    use ops::ops::Additive;
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn range_add_range_sum() {
+        type M = Mint<998244353>;
+
+        let mut rng = rand::rng();
+        for n in 0..50 {
+            let mut naive = Vec::from_iter((0..n).map(|_| M::new(rng.random())));
+            let mut tree = RangeAddFenwickTree::<Additive<M>>::from(naive.clone());
+            assert_eq!(tree.len(), n);
+
+            for _ in 0..200 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                if rng.random_bool(0.5) {
+                    let x = M::new(rng.random());
+                    naive[l..r].iter_mut().for_each(|v| *v += x);
+                    tree.range_add(l..r, x);
+                } else {
+                    assert_eq!(
+                        tree.range_sum(l..r),
+                        naive[l..r].iter().fold(M::new(0), |acc, &v| acc + v)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,472 @@
+use std::{
+    fmt::Debug,
+    ops::{Bound, Range, RangeBounds},
+};
+
+use compress::compress;
+use fenwick::FenwickTree;
+use ops::{marker::Commutative, Group, Monoid, SemiGroup};
+
+/// 可換な半群の値の`N`×`M`行列に対して、一点更新・累積クエリを高速に計算するデータ構造。
+#[derive(Debug, Clone)]
+pub struct FenwickTree2D<T: SemiGroup + Commutative> {
+    /// row-major
+    lefts: Box<[T::Set]>,
+    n: usize,
+    m: usize,
+}
+
+impl<T> FenwickTree2D<T>
+where
+    T: Monoid + Commutative,
+    T::Set: Copy,
+{
+    /// 単位元で初期化する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(*NM*)
+    pub fn new(n: usize, m: usize) -> Self {
+        Self {
+            lefts: vec![T::id(); n * m].into_boxed_slice(),
+            n,
+            m,
+        }
+    }
+
+    /// 行数と列数を返す。
+    pub fn shape(&self) -> (usize, usize) {
+        (self.n, self.m)
+    }
+
+    /// `(0..i, 0..j)`の要素について累積計算した結果を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N* log *M*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than or equal to `N`
+    /// - `j` should be less than or equal to `M`
+    pub fn prefix_query(&self, mut i: usize, j: usize) -> T::Set {
+        assert!(i <= self.n && j <= self.m, "index out of bounds");
+
+        let mut res = T::id();
+        while i > 0 {
+            let row = &self.lefts[(i - 1) * self.m..][..self.m];
+            let mut j = j;
+            while j > 0 {
+                res = T::op(row[j - 1], res);
+                j &= j - 1
+            }
+            i &= i - 1
+        }
+
+        res
+    }
+
+    /// `(i, j)`番目の要素を差分計算する。
+    /// つまり、`T::op(self, additional)`で更新する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N* log *M*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    /// - `j` should be less than `M`
+    pub fn point_update(&mut self, i: usize, j: usize, additional: T::Set) {
+        assert!(i < self.n && j < self.m, "index out of bounds");
+
+        let mut i = i + 1;
+        while i <= self.n {
+            let row = &mut self.lefts[(i - 1) * self.m..][..self.m];
+            let mut j = j + 1;
+            while j <= self.m {
+                row[j - 1] = T::op(row[j - 1], additional);
+                j += j & j.wrapping_neg()
+            }
+            i += i & i.wrapping_neg()
+        }
+    }
+}
+
+impl<T> FenwickTree2D<T>
+where
+    T: Group + Commutative,
+    T::Set: Copy,
+{
+    /// 長方形領域`rows`×`cols`の要素について累積計算した結果を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N* log *M*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_query<R, C>(&self, rows: R, cols: C) -> T::Set
+    where
+        R: RangeBounds<usize>,
+        C: RangeBounds<usize>,
+    {
+        let Range { start: il, end: ir } = parse_range(rows, self.n);
+        let Range { start: jl, end: jr } = parse_range(cols, self.m);
+        if il >= ir || jl >= jr {
+            return T::id();
+        }
+
+        let pos = T::op(self.prefix_query(ir, jr), self.prefix_query(il, jl));
+        let neg = T::op(self.prefix_query(il, jr), self.prefix_query(ir, jl));
+        T::op(pos, T::inv(neg))
+    }
+}
+
+impl<T> From<Vec<Vec<T::Set>>> for FenwickTree2D<T>
+where
+    T: Monoid + Commutative,
+    T::Set: Copy,
+{
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NM*)
+    ///
+    /// # Panics
+    ///
+    /// 各行の長さが等しくなければパニックする。
+    fn from(value: Vec<Vec<T::Set>>) -> Self {
+        let n = value.len();
+        let m = value.first().map_or(0, Vec::len);
+        assert!(value.iter().all(|row| row.len() == m), "ragged matrix");
+
+        let mut lefts = value.into_iter().flatten().collect::<Vec<_>>();
+        // 各行について正順に親を更新する
+        for row in lefts.chunks_exact_mut(m.max(1)) {
+            for j in 0..m {
+                let p = (j + 1) + (1 << (j + 1).trailing_zeros());
+                if p <= m {
+                    row[p - 1] = T::op(row[p - 1], row[j]);
+                }
+            }
+        }
+        // 各列について正順に親を更新する
+        for i in 0..n {
+            let p = (i + 1) + (1 << (i + 1).trailing_zeros());
+            if p <= n {
+                for j in 0..m {
+                    lefts[(p - 1) * m + j] = T::op(lefts[(p - 1) * m + j], lefts[i * m + j]);
+                }
+            }
+        }
+
+        Self {
+            lefts: lefts.into_boxed_slice(),
+            n,
+            m,
+        }
+    }
+}
+
+/// 更新する座標が事前に分かっている場合に、一点更新・長方形領域の累積クエリを計算するデータ構造。
+///
+/// `x`座標を`compress`で座標圧縮し、Fenwick tree の各ノードに`y`座標ごとの Fenwick tree を持たせる。
+/// 点の個数を`N`とすると、空間計算量は *O*(*N* log *N*) となる。
+///
+/// # Example
+///
+/// ```
+/// use fenwick_2d::CompressedFenwickTree2D;
+/// use ops::ops::Additive;
+///
+/// let points = [(1, 1), (3, 5), (1_000_000_000, 2), (3, 5)];
+/// let mut tree = CompressedFenwickTree2D::<Additive<i64>>::new(&points);
+/// for &(x, y) in &points {
+///     tree.point_update(x, y, 1);
+/// }
+///
+/// assert_eq!(tree.prefix_query(4, 6), 3);
+/// assert_eq!(tree.range_query(2.., 2..), 3);
+/// assert_eq!(tree.range_query(..=3, 0..5), 1);
+/// ```
+pub struct CompressedFenwickTree2D<T: SemiGroup + Commutative> {
+    /// 重複を除いて昇順に並べた`x`座標
+    xs: Box<[u32]>,
+    /// `ys[k]`は`k`番目のノードが担当する点の`y`座標を、重複を除いて昇順に並べたもの
+    ys: Box<[Box<[u32]>]>,
+    nodes: Box<[FenwickTree<T>]>,
+}
+
+impl<T> Clone for CompressedFenwickTree2D<T>
+where
+    T: SemiGroup + Commutative,
+    FenwickTree<T>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            xs: self.xs.clone(),
+            ys: self.ys.clone(),
+            nodes: self.nodes.clone(),
+        }
+    }
+}
+
+impl<T> Debug for CompressedFenwickTree2D<T>
+where
+    T: SemiGroup + Commutative,
+    FenwickTree<T>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompressedFenwickTree2D")
+            .field("xs", &self.xs)
+            .field("ys", &self.ys)
+            .field("nodes", &self.nodes)
+            .finish()
+    }
+}
+
+impl<T> CompressedFenwickTree2D<T>
+where
+    T: Monoid + Commutative,
+    T::Set: Copy,
+{
+    /// 更新する座標を登録し、単位元で初期化する。重複があってもよい。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(*N* log² *N*)
+    pub fn new(points: &[(u32, u32)]) -> Self {
+        let (compressed, xs) = compress::<0>(&Vec::from_iter(points.iter().map(|&(x, _)| x)));
+
+        let mut ys = vec![Vec::new(); xs.len()];
+        for (&i, &(_, y)) in compressed.iter().zip(points) {
+            let mut i = i as usize + 1;
+            while i <= xs.len() {
+                ys[i - 1].push(y);
+                i += i & i.wrapping_neg()
+            }
+        }
+        for ys in ys.iter_mut() {
+            ys.sort_unstable();
+            ys.dedup();
+        }
+        let nodes = Box::from_iter(ys.iter().map(|ys| FenwickTree::new(ys.len())));
+
+        Self {
+            xs: xs.into_boxed_slice(),
+            ys: Box::from_iter(ys.into_iter().map(Vec::into_boxed_slice)),
+            nodes,
+        }
+    }
+
+    /// `x < x_max`かつ`y < y_max`を満たす点について累積計算した結果を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log² *N*)
+    pub fn prefix_query(&self, x_max: u32, y_max: u32) -> T::Set {
+        let mut res = T::id();
+        let mut i = self.xs.partition_point(|&x| x < x_max);
+        while i > 0 {
+            let j = self.ys[i - 1].partition_point(|&y| y < y_max);
+            res = T::op(self.nodes[i - 1].prefix_query(j), res);
+            i &= i - 1
+        }
+
+        res
+    }
+
+    /// 点`(x, y)`の値を差分計算する。
+    /// つまり、`T::op(self, additional)`で更新する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log² *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `(x, y)` should be registered in advance
+    pub fn point_update(&mut self, x: u32, y: u32, additional: T::Set) {
+        let mut i = self.xs.binary_search(&x).expect("unregistered point") + 1;
+        while i <= self.xs.len() {
+            let j = self.ys[i - 1]
+                .binary_search(&y)
+                .expect("unregistered point");
+            self.nodes[i - 1].point_update(j, additional);
+            i += i & i.wrapping_neg()
+        }
+    }
+}
+
+impl<T> CompressedFenwickTree2D<T>
+where
+    T: Group + Commutative,
+    T::Set: Copy,
+{
+    /// 長方形領域`xs`×`ys`に含まれる点について累積計算した結果を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log² *N*)
+    pub fn range_query<X, Y>(&self, xs: X, ys: Y) -> T::Set
+    where
+        X: RangeBounds<u32>,
+        Y: RangeBounds<u32>,
+    {
+        let Range {
+            start: mut l,
+            end: mut r,
+        } = search_range(&self.xs, &xs);
+        if l >= r {
+            return T::id();
+        }
+
+        let mut res = T::id();
+        while l != r {
+            if r > l {
+                let Range { start, end } = search_range(&self.ys[r - 1], &ys);
+                res = T::op(res, self.nodes[r - 1].range_query(start..end.max(start)));
+                r &= r - 1
+            } else {
+                let Range { start, end } = search_range(&self.ys[l - 1], &ys);
+                res = T::op(
+                    res,
+                    T::inv(self.nodes[l - 1].range_query(start..end.max(start))),
+                );
+                l &= l - 1
+            }
+        }
+
+        res
+    }
+}
+
+/// `0..len`の部分区間に変換する。
+#[inline]
+fn parse_range<R>(range: R, len: usize) -> Range<usize>
+where
+    R: RangeBounds<usize>,
+{
+    let l = match range.start_bound() {
+        Bound::Included(l) => *l,
+        Bound::Excluded(l) => l + 1,
+        Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(r) => r + 1,
+        Bound::Excluded(r) => *r,
+        Bound::Unbounded => len,
+    };
+    assert!(r <= len, "index out of bounds");
+
+    l..r
+}
+
+/// 昇順に並んだ`sorted`のうち、`range`に含まれる要素の添え字の区間を返す。
+#[inline]
+fn search_range<R>(sorted: &[u32], range: &R) -> Range<usize>
+where
+    R: RangeBounds<u32>,
+{
+    let l = match range.start_bound() {
+        Bound::Included(l) => sorted.partition_point(|v| v < l),
+        Bound::Excluded(l) => sorted.partition_point(|v| v <= l),
+        Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(r) => sorted.partition_point(|v| v <= r),
+        Bound::Excluded(r) => sorted.partition_point(|v| v < r),
+        Bound::Unbounded => sorted.len(),
+    };
+
+    l..r
+}
+
+#[cfg(test)]
+mod tests {
+    use ops::ops::Additive;
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn dense() {
+        let mut rng = rand::rng();
+        for (n, m) in [(0, 0), (3, 0), (1, 1), (7, 13), (16, 16), (30, 5)] {
+            let mut naive = vec![vec![0i64; m]; n];
+            naive
+                .iter_mut()
+                .flatten()
+                .for_each(|v| *v = rng.random_range(-100..100));
+            let mut tree = FenwickTree2D::<Additive<i64>>::from(naive.clone());
+            assert_eq!(tree.shape(), (n, m));
+
+            for _ in 0..500 {
+                let il = rng.random_range(0..=n);
+                let ir = rng.random_range(il..=n);
+                let jl = rng.random_range(0..=m);
+                let jr = rng.random_range(jl..=m);
+                let expected: i64 = naive[il..ir]
+                    .iter()
+                    .map(|row| row[jl..jr].iter().sum::<i64>())
+                    .sum();
+                assert_eq!(tree.range_query(il..ir, jl..jr), expected);
+
+                let prefix: i64 = naive[..ir]
+                    .iter()
+                    .map(|row| row[..jr].iter().sum::<i64>())
+                    .sum();
+                assert_eq!(tree.prefix_query(ir, jr), prefix);
+
+                if n > 0 && m > 0 {
+                    let (i, j) = (rng.random_range(0..n), rng.random_range(0..m));
+                    let x = rng.random_range(-100..100);
+                    naive[i][j] += x;
+                    tree.point_update(i, j, x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compressed() {
+        let mut rng = rand::rng();
+        for (n, max) in [(0, 10), (1, 10), (50, 10), (300, 100), (300, u32::MAX)] {
+            let points = Vec::from_iter(
+                (0..n).map(|_| (rng.random_range(0..=max), rng.random_range(0..=max))),
+            );
+            let mut naive = vec![0i64; n];
+            let mut tree = CompressedFenwickTree2D::<Additive<i64>>::new(&points);
+
+            for _ in 0..500 {
+                if n > 0 {
+                    let k = rng.random_range(0..n);
+                    let x = rng.random_range(-100..100);
+                    naive[k] += x;
+                    tree.point_update(points[k].0, points[k].1, x);
+                }
+
+                let [xl, xr] = [rng.random_range(0..=max), rng.random_range(0..=max)];
+                let [yl, yr] = [rng.random_range(0..=max), rng.random_range(0..=max)];
+                let in_range =
+                    |&(x, y): &(u32, u32)| (xl..=xr).contains(&x) && (yl..yr).contains(&y);
+                let expected: i64 = points
+                    .iter()
+                    .zip(&naive)
+                    .filter(|(p, _)| in_range(p))
+                    .map(|(_, v)| v)
+                    .sum();
+                assert_eq!(tree.range_query(xl..=xr, yl..yr), expected);
+
+                let prefix: i64 = points
+                    .iter()
+                    .zip(&naive)
+                    .filter(|((x, y), _)| *x < xr && *y < yr)
+                    .map(|(_, v)| v)
+                    .sum();
+                assert_eq!(tree.prefix_query(xr, yr), prefix);
+            }
+        }
+    }
+}
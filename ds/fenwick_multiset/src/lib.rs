@@ -0,0 +1,418 @@
+use compress::compress;
+use fenwick::FenwickTree;
+use ops::ops::Additive;
+use simd_bit::{LaneCount, SimdBIT, SupportedLaneCount};
+
+/// 多重集合の各値の個数を管理する Fenwick tree。
+pub trait Counter {
+    /// 全要素を 0 で初期化する。
+    fn with_len(n: usize) -> Self;
+
+    /// `i`番目の要素に`diff`を加算する。
+    fn add(&mut self, i: usize, diff: i32);
+
+    /// `i`番目の要素を返す。
+    fn get(&self, i: usize) -> i32;
+
+    /// `0..n`番目の要素の和を返す。
+    fn prefix_sum(&self, n: usize) -> i32;
+
+    /// 累積和が`k`以下となる最大の要素数を返す。累積和の総和は`k`より大きいとする。
+    fn upper_bound(&self, k: i32) -> usize;
+}
+
+impl Counter for FenwickTree<Additive<i32>> {
+    fn with_len(n: usize) -> Self {
+        FenwickTree::new(n)
+    }
+
+    fn add(&mut self, i: usize, diff: i32) {
+        self.point_update(i, diff);
+    }
+
+    fn get(&self, i: usize) -> i32 {
+        self.range_query(i..=i)
+    }
+
+    fn prefix_sum(&self, n: usize) -> i32 {
+        self.prefix_query(n)
+    }
+
+    fn upper_bound(&self, k: i32) -> usize {
+        self.partition_point(|&sum| sum <= k).0
+    }
+}
+
+impl<const B: usize> Counter for SimdBIT<i32, B>
+where
+    LaneCount<B>: SupportedLaneCount,
+{
+    fn with_len(n: usize) -> Self {
+        SimdBIT::from(vec![0; n])
+    }
+
+    fn add(&mut self, i: usize, diff: i32) {
+        self.point_add(i, diff);
+    }
+
+    fn get(&self, i: usize) -> i32 {
+        SimdBIT::get(self, i)
+    }
+
+    fn prefix_sum(&self, n: usize) -> i32 {
+        SimdBIT::prefix_sum(self, n)
+    }
+
+    fn upper_bound(&self, k: i32) -> usize {
+        self.partition_point(|sum| sum <= k).0
+    }
+}
+
+/// `0..N`の整数からなる多重集合。順序統計量を *Θ*(log *N*) 時間で計算する。
+///
+/// 値の範囲が広い場合は、`C`に[`SimdBIT`]を指定すると高速になる。
+///
+/// # Example
+///
+/// ```
+/// use fenwick_multiset::FenwickMultiset;
+/// use simd_bit::SimdBIT;
+///
+/// let mut set = FenwickMultiset::<SimdBIT<i32, 16>>::new(100);
+/// for x in [3, 1, 4, 1, 5] {
+///     set.insert(x);
+/// }
+///
+/// assert_eq!(set.len(), 5);
+/// assert_eq!(set.count(1), 2);
+/// assert_eq!(set.kth(2), Some(3));
+/// assert_eq!(set.rank(4), 3);
+/// assert_eq!(set.prev(3), Some(1));
+/// assert_eq!(set.next(6), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FenwickMultiset<C = FenwickTree<Additive<i32>>>
+where
+    C: Counter,
+{
+    counter: C,
+    universe: usize,
+    len: usize,
+}
+
+impl<C> FenwickMultiset<C>
+where
+    C: Counter,
+{
+    /// 値の範囲が`0..n`である空の多重集合を作る。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(*N*)
+    pub fn new(n: usize) -> Self {
+        Self {
+            counter: C::with_len(n),
+            universe: n,
+            len: 0,
+        }
+    }
+
+    /// 要素数を返す。
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// `x`を 1 つ追加する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `x` should be less than `N`
+    pub fn insert(&mut self, x: usize) {
+        assert!(x < self.universe, "index out of bounds");
+
+        self.counter.add(x, 1);
+        self.len += 1;
+    }
+
+    /// `x`を 1 つ削除する。`x`が含まれていなければ`false`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn remove(&mut self, x: usize) -> bool {
+        if self.count(x) == 0 {
+            return false;
+        }
+
+        self.counter.add(x, -1);
+        self.len -= 1;
+        true
+    }
+
+    /// `x`の個数を返す。
+    pub fn count(&self, x: usize) -> usize {
+        if x < self.universe {
+            self.counter.get(x) as usize
+        } else {
+            0
+        }
+    }
+
+    /// 昇順に並べたとき`k`番目（0-indexed）の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn kth(&self, k: usize) -> Option<usize> {
+        if k < self.len {
+            Some(self.counter.upper_bound(k as i32))
+        } else {
+            None
+        }
+    }
+
+    /// `x`未満の要素の個数を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn rank(&self, x: usize) -> usize {
+        self.counter.prefix_sum(x.min(self.universe)) as usize
+    }
+
+    /// `x`未満で最大の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn prev(&self, x: usize) -> Option<usize> {
+        self.rank(x).checked_sub(1).and_then(|k| self.kth(k))
+    }
+
+    /// `x`以上で最小の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn next(&self, x: usize) -> Option<usize> {
+        self.kth(self.rank(x))
+    }
+}
+
+/// 事前に与えた値からなる多重集合。値を座標圧縮して[`FenwickMultiset`]で管理する。
+///
+/// # Example
+///
+/// ```
+/// use fenwick_multiset::CompressedFenwickMultiset;
+///
+/// let mut set: CompressedFenwickMultiset = CompressedFenwickMultiset::new(&[10, 1_000_000_000, 42]);
+/// set.insert(42);
+/// set.insert(1_000_000_000);
+///
+/// assert_eq!(set.kth(1), Some(1_000_000_000));
+/// assert_eq!(set.rank(100), 1);
+/// assert_eq!(set.next(43), Some(1_000_000_000));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompressedFenwickMultiset<C = FenwickTree<Additive<i32>>>
+where
+    C: Counter,
+{
+    /// 重複を除いて昇順に並べた値
+    keys: Box<[u32]>,
+    inner: FenwickMultiset<C>,
+}
+
+impl<C> CompressedFenwickMultiset<C>
+where
+    C: Counter,
+{
+    /// 追加しうる値を登録し、空の多重集合を作る。重複があってもよい。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(*N*)
+    pub fn new(keys: &[u32]) -> Self {
+        let (_, keys) = compress::<0>(keys);
+        let inner = FenwickMultiset::new(keys.len());
+
+        Self {
+            keys: keys.into_boxed_slice(),
+            inner,
+        }
+    }
+
+    /// 要素数を返す。
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// `x`を 1 つ追加する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `x` should be registered in advance
+    pub fn insert(&mut self, x: u32) {
+        let i = self.keys.binary_search(&x).expect("unregistered key");
+        self.inner.insert(i);
+    }
+
+    /// `x`を 1 つ削除する。`x`が含まれていなければ`false`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn remove(&mut self, x: u32) -> bool {
+        self.keys
+            .binary_search(&x)
+            .is_ok_and(|i| self.inner.remove(i))
+    }
+
+    /// `x`の個数を返す。
+    pub fn count(&self, x: u32) -> usize {
+        self.keys
+            .binary_search(&x)
+            .map_or(0, |i| self.inner.count(i))
+    }
+
+    /// 昇順に並べたとき`k`番目（0-indexed）の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn kth(&self, k: usize) -> Option<u32> {
+        self.inner.kth(k).map(|i| self.keys[i])
+    }
+
+    /// `x`未満の要素の個数を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn rank(&self, x: u32) -> usize {
+        self.inner.rank(self.keys.partition_point(|&key| key < x))
+    }
+
+    /// `x`未満で最大の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn prev(&self, x: u32) -> Option<u32> {
+        self.rank(x).checked_sub(1).and_then(|k| self.kth(k))
+    }
+
+    /// `x`以上で最小の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    pub fn next(&self, x: u32) -> Option<u32> {
+        self.kth(self.rank(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::Rng;
+
+    use super::*;
+
+    fn check<C: Counter>(n: usize) {
+        let mut rng = rand::rng();
+        let mut naive = BTreeMap::<usize, usize>::new();
+        let mut set = FenwickMultiset::<C>::new(n);
+
+        for _ in 0..2000 {
+            let x = rng.random_range(0..=n);
+            match rng.random_range(0..3) {
+                0 if x < n => {
+                    *naive.entry(x).or_default() += 1;
+                    set.insert(x);
+                }
+                1 => {
+                    let expected = naive.get(&x).is_some_and(|&c| c > 0);
+                    if expected {
+                        *naive.get_mut(&x).unwrap() -= 1;
+                    }
+                    assert_eq!(set.remove(x), expected);
+                }
+                _ => {}
+            }
+
+            let sorted =
+                Vec::from_iter(naive.iter().flat_map(|(&x, &c)| std::iter::repeat_n(x, c)));
+            assert_eq!(set.len(), sorted.len());
+            assert_eq!(set.count(x), naive.get(&x).copied().unwrap_or(0));
+            let k = rng.random_range(0..=sorted.len());
+            assert_eq!(set.kth(k), sorted.get(k).copied());
+            let rank = sorted.partition_point(|&v| v < x);
+            assert_eq!(set.rank(x), rank);
+            assert_eq!(set.prev(x), rank.checked_sub(1).map(|k| sorted[k]));
+            assert_eq!(set.next(x), sorted.get(rank).copied());
+        }
+    }
+
+    #[test]
+    fn fenwick() {
+        for n in [0, 1, 2, 7, 64, 100] {
+            check::<FenwickTree<Additive<i32>>>(n);
+        }
+    }
+
+    #[test]
+    fn simd() {
+        for n in [0, 1, 2, 7, 64, 100, 1000] {
+            check::<SimdBIT<i32, 4>>(n);
+            check::<SimdBIT<i32, 16>>(n);
+        }
+    }
+
+    #[test]
+    fn compressed() {
+        let mut rng = rand::rng();
+        let keys = Vec::from_iter((0..100).map(|_| rng.random::<u32>()));
+        let mut naive = Vec::new();
+        let mut set = CompressedFenwickMultiset::<SimdBIT<i32, 8>>::new(&keys);
+
+        for _ in 0..1000 {
+            let x = keys[rng.random_range(0..keys.len())];
+            if rng.random_bool(0.6) {
+                naive.insert(naive.partition_point(|&v| v < x), x);
+                set.insert(x);
+            } else if let Ok(i) = naive.binary_search(&x) {
+                naive.remove(i);
+                assert!(set.remove(x));
+            } else {
+                assert!(!set.remove(x));
+            }
+
+            let y = rng.random::<u32>();
+            let rank = naive.partition_point(|&v| v < y);
+            assert_eq!(set.len(), naive.len());
+            assert_eq!(set.rank(y), rank);
+            assert_eq!(set.prev(y), rank.checked_sub(1).map(|k| naive[k]));
+            assert_eq!(set.next(y), naive.get(rank).copied());
+            assert_eq!(set.count(x), naive.iter().filter(|&&v| v == x).count());
+        }
+    }
+}
@@ -3,113 +3,89 @@ use std::{
     ops::{Range, RangeBounds},
 };
 
-use ops::Monoid;
+use ops::{Identity, Monoid, MonoidActionWithSize, SemiGroup};
 
-/// 作用素モノイド M とモノイド S が下記の条件を満たすとき、その組を遅延伝搬構造と呼ぶ。
-///
-/// - ∀ f, g ∈ M, ∀ x ∈ S, f * (g * x) = (f * g) * x
-/// - e ∈ M, ∀ x ∈ S, e * x = x
-/// - ∀ f ∈ M, ∀ x, y ∈ S, (f * x) + (f * y) = f * (x + y)
-//
-// 遅延伝搬の部分だけ記述すれば使えるようにしたい。
-// trait の自動実装が難しく、orphan rule を回避するためにこうなった。
-//
-// 最適化により、match 式は消えるはず
-pub enum LazyPropagation<M: Monoid, S: Monoid> {
-    SizeDependent(Box<dyn Fn(M::Set, S::Set, usize) -> S::Set>),
-    SizeIndependent(Box<dyn Fn(M::Set, S::Set) -> S::Set>),
-}
-
-impl<M, S> LazyPropagation<M, S>
-where
-    M: Monoid,
-    S: Monoid,
-{
-    /// 区間幅を利用する場合
-    pub fn new_size_dependent(f: impl Fn(M::Set, S::Set, usize) -> S::Set + 'static) -> Self {
-        Self::SizeDependent(Box::new(f))
-    }
-
-    /// 区間幅を利用しない場合
-    pub fn new_size_independent(f: impl Fn(M::Set, S::Set) -> S::Set + 'static) -> Self {
-        Self::SizeIndependent(Box::new(f))
-    }
+type Map<A> = <<A as MonoidActionWithSize>::Map as SemiGroup>::Set;
+type Arg<A> = <<A as MonoidActionWithSize>::Arg as SemiGroup>::Set;
 
-    fn is_size_dependent(&self) -> bool {
-        match self {
-            LazyPropagation::SizeDependent(_) => true,
-            LazyPropagation::SizeIndependent(_) => false,
-        }
-    }
-}
-
-pub struct LazySegtree<M, S>
+/// 区間作用・区間クエリを処理するデータ構造。
+///
+/// 作用素の合成順序などは[`ops::MonoidAction`]を参照すること。
+pub struct LazySegtree<A>
 where
-    M: Monoid<Set: Copy>,
-    S: Monoid<Set: Copy>,
+    A: MonoidActionWithSize<Map: Monoid<Set: Copy>, Arg: Monoid<Set: Copy>>,
 {
-    // 要素数が n のとき、配列のサイズは 2n とし、ルートにはアクセスしないことにする。
-    // 配列は 1-origin とする。
-    data: Box<[S::Set]>,
-    maps: Box<[M::Set]>,
-    // 完全二分木とは限らないので必要に応じてサイズを前計算する
-    size: Option<Box<[usize]>>,
-
-    lazy: LazyPropagation<M, S>,
+    /// full binary tree. 配列は 1-origin とし、ルートは`data[1]`とする。
+    /// `data.len() = 2 * offset`
+    data: Box<[Arg<A>]>,
+    /// 内部ノードに保留された作用素。`maps.len() = offset`
+    maps: Box<[Map<A>]>,
+    offset: usize,
+    net_len: usize,
 
     // 遅延伝搬・再計算で訪問する頂点を降順にメモする
     // TODO: [T; usize::BITS * 2] のラッパー定義し、Vec のように使いたい
     ancestors: Vec<usize>,
 }
 
-/// データセグメントを更新してから、遅延評価関数を合成する
-//
-// メソッドにするには借用規則の回避が面倒
-macro_rules! push {
-    ( $this:expr, $i:expr, $new_map:expr ) => {
-        $this.data[$i] = match &$this.lazy {
-            LazyPropagation::SizeDependent(f) => {
-                let size = $this.size.as_ref().unwrap()[$i];
-                f($new_map, $this.data[$i], size)
-            }
-            LazyPropagation::SizeIndependent(f) => f($new_map, $this.data[$i]),
-        };
-
-        if let Some(map) = $this.maps.get_mut($i) {
-            // 時系列順に作用素を合成
-            *map = M::op(*map, $new_map)
-        }
-    };
-}
-
-impl<M, S> LazySegtree<M, S>
+impl<A> LazySegtree<A>
 where
-    M: Monoid<Set: Copy>,
-    S: Monoid<Set: Copy>,
+    A: MonoidActionWithSize<Map: Monoid<Set: Copy>, Arg: Monoid<Set: Copy>>,
 {
     /// 適切にオフセットを追加する。
     #[inline]
-    fn into_inner_range<R>(&self, range: R) -> Range<usize>
+    fn parse_range<R>(&self, range: R) -> Range<usize>
     where
         R: RangeBounds<usize>,
     {
-        let offset = self.data.len() >> 1;
-
         // 最適化により、分岐は消える。インライン化してもよさそう。
         let l = match range.start_bound() {
-            std::ops::Bound::Included(l) => l + offset,
-            std::ops::Bound::Excluded(l) => l + offset + 1,
-            std::ops::Bound::Unbounded => offset,
+            std::ops::Bound::Included(l) => l + self.offset,
+            std::ops::Bound::Excluded(l) => l + self.offset + 1,
+            std::ops::Bound::Unbounded => self.offset,
         };
         let r = match range.end_bound() {
-            std::ops::Bound::Included(r) => r + offset + 1,
-            std::ops::Bound::Excluded(r) => r + offset,
-            std::ops::Bound::Unbounded => self.data.len(),
+            std::ops::Bound::Included(r) => r + self.offset + 1,
+            std::ops::Bound::Excluded(r) => r + self.offset,
+            std::ops::Bound::Unbounded => self.offset + self.net_len,
         };
+        assert!(r <= self.offset + self.net_len, "index out of bounds");
 
         l..r
     }
 
+    /// `i`番目のノードに作用素を作用させてから、保留中の作用素に合成する。
+    #[inline(always)]
+    fn push(&mut self, i: usize, map: Map<A>) {
+        // 完全二分木なので、区間幅は深さから計算できる
+        self.data[i] = A::act_with_size(map, self.data[i], self.offset >> i.ilog2());
+
+        if let Some(m) = self.maps.get_mut(i) {
+            // 時系列順に作用素を合成
+            *m = A::Map::op(*m, map)
+        }
+    }
+
+    /// `i`番目のノードに保留された作用素を子ノードに伝搬する。
+    #[inline(always)]
+    fn propagate(&mut self, i: usize) {
+        let map = std::mem::replace(&mut self.maps[i], A::Map::id());
+
+        self.push(i << 1, map);
+        self.push((i << 1) | 1, map);
+    }
+
+    /// 葉`i`の祖先に保留された作用素を上から順に伝搬する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    fn propagate_ancestors(&mut self, i: usize) {
+        for d in (1..=self.offset.trailing_zeros()).rev() {
+            self.propagate(i >> d);
+        }
+    }
+
     /// # Time Complexity
     ///
     /// *O*(log *N*)
@@ -149,44 +125,41 @@ where
     /// # Panics
     ///
     /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
-    pub fn range_query<R>(&mut self, range: R) -> S::Set
+    pub fn range_query<R>(&mut self, range: R) -> Arg<A>
     where
         R: RangeBounds<usize>,
     {
-        let range = self.into_inner_range(range);
+        let range = self.parse_range(range);
         if range.is_empty() {
-            return S::id();
+            return A::Arg::id();
         }
         self.write_ancestors(range.clone());
 
         // 作用素を必要な分だけ上から順に遅延評価する。
-        for i in self.ancestors.drain(..).rev() {
-            let map = std::mem::replace(&mut self.maps[i], M::id());
-
-            push!(self, i << 1, map);
-            push!(self, (i << 1) | 1, map);
+        while let Some(i) = self.ancestors.pop() {
+            self.propagate(i);
         }
 
         // クエリの答えを計算する
         {
             let Range { start, end } = range;
             let [mut l, mut r] = [start >> start.trailing_zeros(), end >> end.trailing_zeros()];
-            let [mut acc_l, mut acc_r] = [S::id(); 2];
+            let [mut acc_l, mut acc_r] = [A::Arg::id(); 2];
             while {
                 if l >= r {
-                    acc_l = S::op(acc_l, self.data[l]);
+                    acc_l = A::Arg::op(acc_l, self.data[l]);
                     l += 1;
                     l >>= l.trailing_zeros()
                 } else {
                     r -= 1;
-                    acc_r = S::op(self.data[r], acc_r);
+                    acc_r = A::Arg::op(self.data[r], acc_r);
                     r >>= r.trailing_zeros()
                 }
 
                 l != r
             } {}
 
-            S::op(acc_l, acc_r)
+            A::Arg::op(acc_l, acc_r)
         }
     }
 
@@ -199,11 +172,11 @@ where
     /// # Panics
     ///
     /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
-    pub fn range_update<R>(&mut self, range: R, map: M::Set)
+    pub fn range_update<R>(&mut self, range: R, map: Map<A>)
     where
         R: RangeBounds<usize>,
     {
-        let range = self.into_inner_range(range);
+        let range = self.parse_range(range);
         // 早期リターンしないと更新処理がバグる
         if range.is_empty() {
             return;
@@ -211,11 +184,8 @@ where
         self.write_ancestors(range.clone());
 
         // 作用素を必要な分だけ上から順に遅延評価する。
-        for i in self.ancestors.iter().copied().rev() {
-            let map = std::mem::replace(&mut self.maps[i], M::id());
-
-            push!(self, i << 1, map);
-            push!(self, (i << 1) | 1, map);
+        for k in (0..self.ancestors.len()).rev() {
+            self.propagate(self.ancestors[k]);
         }
 
         // 必要な分だけ更新する
@@ -225,12 +195,12 @@ where
 
             while {
                 if l >= r {
-                    push!(self, l, map);
+                    self.push(l, map);
                     l += 1;
                     l >>= l.trailing_zeros()
                 } else {
                     r -= 1;
-                    push!(self, r, map);
+                    self.push(r, map);
                     r >>= r.trailing_zeros()
                 }
 
@@ -240,281 +210,335 @@ where
 
         // 必要な分だけ親ノードの値を再計算する
         for i in self.ancestors.drain(..) {
-            self.data[i] = S::op(self.data[i << 1], self.data[(i << 1) | 1])
+            self.data[i] = A::Arg::op(self.data[i << 1], self.data[(i << 1) | 1])
         }
     }
 
+    /// `i`番目の要素を返す。
+    ///
     /// # Time Complexity
     ///
-    /// *O*(*N*)
-    pub fn data(&mut self) -> &[S::Set] {
-        // 遅延伝搬する
-        for i in 0..self.data.len() >> 1 {
-            let map = std::mem::replace(&mut self.maps[i], M::id());
-            push!(self, i << 1, map);
-            push!(self, (i << 1) | 1, map);
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_get(&self, i: usize) -> Arg<A> {
+        assert!(i < self.net_len, "index out of bounds");
+
+        // 祖先に保留された作用素ほど新しいので、下から順に作用させる
+        let mut i = i + self.offset;
+        let mut x = self.data[i];
+        while i > 1 {
+            i >>= 1;
+            x = A::act_with_size(self.maps[i], x, 1);
         }
 
-        &self.data[self.data.len() >> 1..]
+        x
     }
-}
 
-impl<M, S> LazySegtree<M, S>
-where
-    M: Monoid<Set: Copy>,
-    S: Monoid<Set: Copy>,
-{
-    /// # Time COmplexity
+    /// `i`番目の要素を`value`で置き換える。
     ///
-    /// *Θ*(*N*)
-    pub fn new(value: Vec<S::Set>, lazy_propagation: LazyPropagation<M, S>) -> Self {
-        let n = value.len();
-
-        let data = {
-            let mut data = Vec::with_capacity(n << 1);
-            {
-                let uninit = data.spare_capacity_mut();
-                for (i, v) in (n..n << 1).zip(value) {
-                    uninit[i].write(v);
-                }
-                for i in (1..n).rev() {
-                    // SAFETY: 子孫は初期化済み
-                    let v = S::op(unsafe { uninit[i << 1].assume_init() }, unsafe {
-                        uninit[(i << 1) | 1].assume_init()
-                    });
-                    uninit[i].write(v);
-                }
-                uninit[0].write(S::id());
-            }
-            // SAFETY: [0, 2n) を初期化した
-            unsafe { data.set_len(n << 1) };
-
-            data.into_boxed_slice()
-        };
-
-        let size = lazy_propagation.is_size_dependent().then_some({
-            let mut size = Vec::with_capacity(n << 1);
-            {
-                let uninit = size.spare_capacity_mut();
-                for i in n..n << 1 {
-                    uninit[i].write(1);
-                }
-                for i in (1..n).rev() {
-                    // SAFETY: 直前までに初期化している
-                    uninit[i].write(
-                        unsafe { uninit[i << 1].assume_init() }
-                            + unsafe { uninit[(i << 1) | 1].assume_init() },
-                    );
-                }
-                uninit[0].write(0);
-            }
-            // SAFETY: [0, 2n) を初期化した
-            unsafe { size.set_len(n << 1) };
-
-            size.into_boxed_slice()
-        });
-
-        Self {
-            data,
-            maps: Vec::from_iter(std::iter::repeat_n(M::id(), n)).into_boxed_slice(),
-            size,
-            lazy: lazy_propagation,
-            // capacity の制約上 n.next_power_of_two() はオーバーフローしない
-            ancestors: Vec::with_capacity(n.next_power_of_two().ilog2() as usize * 2),
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_set(&mut self, i: usize, value: Arg<A>) {
+        assert!(i < self.net_len, "index out of bounds");
+
+        let mut i = i + self.offset;
+        self.propagate_ancestors(i);
+        self.data[i] = value;
+        while i > 1 {
+            i >>= 1;
+            self.data[i] = A::Arg::op(self.data[i << 1], self.data[(i << 1) | 1])
         }
     }
-}
 
-impl<M, S> Debug for LazySegtree<M, S>
-where
-    M: Monoid<Set: Copy + Debug>,
-    S: Monoid<Set: Copy + Debug>,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("LazySegtree")
-            .field("data", &self.data)
-            .field("maps", &self.maps)
-            .field("size", &self.size)
-            .field("lazy", &"/* inaccessible /*")
-            .field("ancestors", &self.ancestors)
-            .finish()
-    }
-}
-
-/*
-pub trait MonoidAction {
-    type F: Monoid<Set: Copy>;
-    type Arg: Monoid<Set: Copy>;
-
-    fn act(
-        f: <Self::F as SemiGroup>::Set,
-        arg: <Self::Arg as SemiGroup>::Set,
-    ) -> <Self::Arg as SemiGroup>::Set;
-}
-
-pub struct LazySegtree<A>
-where
-    A: MonoidAction,
-{
-    // full binary tree
-    tree: Box<[(<A::F as SemiGroup>::Set, <A::Arg as SemiGroup>::Set)]>,
-    offset: usize,
-    net_len: usize,
-}
-
-impl<A> LazySegtree<A>
-where
-    A: MonoidAction,
-{
-    /// Returns half-open interval [l, r)
+    /// `pred(l..r)`が`true`となる最大の`r`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
     ///
     /// # Panics
     ///
-    /// - `range` should be within bounds
-    fn parse_range<R>(&self, range: R) -> [usize; 2]
+    /// - `l` should be less than or equal to `N`
+    pub fn partition_right<F>(&mut self, l: usize, mut pred: F) -> usize
     where
-        R: RangeBounds<usize>,
+        F: FnMut(Arg<A>) -> bool,
     {
-        // overflow results in empty range
-        let l = match range.start_bound() {
-            std::ops::Bound::Included(&l) => l,
-            std::ops::Bound::Excluded(&l) => l.wrapping_sub(1),
-            std::ops::Bound::Unbounded => 0,
-        };
-        let r = match range.end_bound() {
-            std::ops::Bound::Included(&r) => r.wrapping_add(1),
-            std::ops::Bound::Excluded(&r) => r,
-            std::ops::Bound::Unbounded => self.net_len,
-        };
-        assert!(l < self.net_len && r <= self.net_len, "index out of bounds");
-
-        [l + self.offset, r + self.offset]
-    }
-
-    #[inline(always)]
-    fn reflect_then_propagate(&mut self, Range { start, end }: Range<usize>) {
-        let mut propagate_at = |p: usize| {
-            let (f, arg) = self.tree[p];
-            self.tree[p] = (A::F::id(), A::act(f, arg));
-
-            if let Some(c) = self.tree.as_chunks_mut::<2>().0.get_mut(p) {
-                c[0].0 = A::F::op(f, c[0].0);
-                c[1].0 = A::F::op(f, c[1].0);
-            }
-        };
-
-        let end = end - 1;
-        let d = usize::BITS - (start ^ end).leading_zeros();
-
-        // propagate in top-to-bottom order
-        for d in (d..usize::BITS - start.leading_zeros()).rev() {
-            propagate_at(start >> d);
-        }
-        for d in (start.trailing_zeros()..d).rev() {
-            propagate_at(start >> d);
+        assert!(l <= self.net_len, "index out of bounds");
+        if l == self.net_len {
+            return self.net_len;
         }
-        for d in (end.trailing_zeros()..d).rev() {
-            propagate_at(end >> d);
-        }
-    }
 
-    #[inline(always)]
-    fn recalculate(&mut self, Range { start, end }: Range<usize>) {
-        let mut recalculate_at =
-            |p: usize| self.tree[p].1 = A::Arg::op(self.tree[p << 1].1, self.tree[(p << 1) | 1].1);
+        let mut l = l + self.offset;
+        self.propagate_ancestors(l);
+        l >>= l.trailing_zeros();
 
-        let end = end - 1;
-        let d = usize::BITS - (start ^ end).leading_zeros();
+        let mut acc = A::Arg::id();
+        let mut temp;
 
-        // recalculate bottom-to-top order
-        for d in 1 + start.trailing_zeros()..d {
-            recalculate_at(start >> d);
-        }
-        for d in 1 + end.trailing_zeros()..d {
-            recalculate_at(end >> d);
+        // go up
+        while {
+            temp = A::Arg::op(acc, self.data[l]);
+            pred(temp)
+        } {
+            acc = temp;
+            l += 1;
+            l >>= l.trailing_zeros();
+
+            if l == 1 {
+                return self.net_len;
+            }
         }
-        for d in d.max(1)..usize::BITS - start.leading_zeros() {
-            recalculate_at(start >> d);
+
+        // go down
+        while l < self.offset {
+            self.propagate(l);
+            l <<= 1;
+            temp = A::Arg::op(acc, self.data[l]);
+            if pred(temp) {
+                acc = temp;
+                l |= 1;
+            }
         }
+
+        (l ^ self.offset).min(self.net_len)
     }
 
+    /// `pred(l..r)`が`true`となる最小の`l`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
     /// # Time Complexity
     ///
-    /// *O*(log *N*)
-    pub fn range_update<R>(&mut self, range: R, f: <A::F as SemiGroup>::Set)
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `r` should be less than or equal to `N`
+    pub fn partition_left<F>(&mut self, r: usize, mut pred: F) -> usize
     where
-        R: RangeBounds<usize>,
+        F: FnMut(Arg<A>) -> bool,
     {
-        let [l, r] = self.parse_range(range);
-        if (l..r).is_empty() {
-            return;
+        assert!(r <= self.net_len, "index out of bounds");
+        if r == 0 {
+            return 0;
         }
 
-        self.reflect_then_propagate(l..r);
+        let mut r = r + self.offset;
+        self.propagate_ancestors(r - 1);
 
-        // update
-        {
-            let [mut l, mut r] = [l >> l.trailing_zeros(), r >> r.trailing_zeros()];
+        let mut acc = A::Arg::id();
+        let mut temp;
 
-            while {
-                if l >= r {
-                    self.tree[l].0 = A::F::op(f, self.tree[l].0);
-                    l += 1;
-                    l >>= l.trailing_zeros();
-                } else {
-                    r -= 1;
-                    self.tree[r].0 = A::F::op(f, self.tree[r].0);
-                    r >>= r.trailing_zeros();
-                }
+        // go up
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 {
+                r >>= 1
+            }
 
-                l != r
-            } {}
+            temp = A::Arg::op(self.data[r], acc);
+            if !pred(temp) {
+                break;
+            }
+            if r.is_power_of_two() {
+                return 0;
+            }
+            acc = temp;
         }
 
-        self.recalculate(l..r);
+        // go down
+        while r < self.offset {
+            self.propagate(r);
+            r = (r << 1) | 1;
+            temp = A::Arg::op(self.data[r], acc);
+            if pred(temp) {
+                acc = temp;
+                r ^= 1;
+            }
+        }
+
+        (r ^ self.offset) + 1
     }
 
     /// # Time Complexity
     ///
-    /// *O*(log *N*)
-    pub fn range_query<R>(&mut self, range: R) -> <A::Arg as SemiGroup>::Set
-    where
-        R: RangeBounds<usize>,
-    {
-        let [l, r] = self.parse_range(range);
-        if (l..r).is_empty() {
-            return A::Arg::id();
+    /// *O*(*N*)
+    pub fn data(&mut self) -> &[Arg<A>] {
+        // 遅延伝搬する
+        for i in 1..self.offset {
+            self.propagate(i);
         }
 
-        self.reflect_then_propagate(l..r);
+        &self.data[self.offset..][..self.net_len]
+    }
+}
 
-        let [mut l, mut r] = [l >> l.trailing_zeros(), r >> r.trailing_zeros()];
-        let [mut acc_l, mut acc_r] = [A::Arg::id(); 2];
-        while {
-            if l >= r {
-                acc_l = A::Arg::op(acc_l, self.tree[l].1);
-                l += 1;
-                l >>= l.trailing_zeros();
-            } else {
-                r -= 1;
-                acc_r = A::Arg::op(self.tree[l].1, acc_r);
-                r >>= r.trailing_zeros();
-            }
+impl<A> From<Vec<Arg<A>>> for LazySegtree<A>
+where
+    A: MonoidActionWithSize<Map: Monoid<Set: Copy>, Arg: Monoid<Set: Copy>>,
+{
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    ///
+    /// # Panics
+    ///
+    /// - `value.len()` should be less than `isize::MAX`
+    fn from(value: Vec<Arg<A>>) -> Self {
+        assert!(
+            value.len() < usize::MAX / 2,
+            "given data is too large to allocate buffer."
+        );
+
+        // never overflow
+        let offset = value.len().next_power_of_two();
+        let net_len = value.len();
+
+        let mut data = Vec::with_capacity(offset << 1);
+        data.extend(std::iter::repeat_n(A::Arg::id(), offset));
+        data.extend(value);
+        data.resize(offset << 1, A::Arg::id());
+
+        let mut data = data.into_boxed_slice();
+        for i in (1..offset).rev() {
+            data[i] = A::Arg::op(data[i << 1], data[(i << 1) | 1])
+        }
 
-            l != r
-        } {}
+        Self {
+            data,
+            maps: vec![A::Map::id(); offset].into_boxed_slice(),
+            offset,
+            net_len,
+            ancestors: Vec::with_capacity(offset.ilog2() as usize * 2),
+        }
+    }
+}
 
-        A::Arg::op(acc_l, acc_r)
+// `A`に余計な制約を課さないように、手動で実装する
+impl<A> Clone for LazySegtree<A>
+where
+    A: MonoidActionWithSize<Map: Monoid<Set: Copy>, Arg: Monoid<Set: Copy>>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            maps: self.maps.clone(),
+            offset: self.offset,
+            net_len: self.net_len,
+            ancestors: self.ancestors.clone(),
+        }
     }
 }
 
-impl<A> From<Vec<<A::Arg as SemiGroup>::Set>> for LazySegtree<A>
+impl<A> Debug for LazySegtree<A>
 where
-    A: MonoidAction,
+    A: MonoidActionWithSize<Map: Monoid<Set: Copy + Debug>, Arg: Monoid<Set: Copy + Debug>>,
 {
-    fn from(value: Vec<<A::Arg as SemiGroup>::Set>) -> Self {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazySegtree")
+            .field("data", &self.data)
+            .field("maps", &self.maps)
+            .field("offset", &self.offset)
+            .field("net_len", &self.net_len)
+            .finish()
     }
 }
 
-*/
+#[cfg(test)]
+mod tests {
+    use ops::{
+        ops::{Additive, Max},
+        MonoidAction, MonoidActionWithSize,
+    };
+    use rand::Rng;
+
+    use super::*;
+
+    /// 区間加算・区間最大値
+    struct AddMax;
+
+    impl MonoidAction for AddMax {
+        type Map = Additive<i64>;
+        type Arg = Max<i64>;
+
+        fn act(f: i64, x: i64) -> i64 {
+            if x == i64::MIN {
+                x
+            } else {
+                x + f
+            }
+        }
+    }
+
+    /// 区間加算・区間和
+    struct AddSum;
+
+    impl MonoidActionWithSize for AddSum {
+        type Map = Additive<i64>;
+        type Arg = Additive<i64>;
+
+        fn act_with_size(f: i64, x: i64, size: usize) -> i64 {
+            x + f * size as i64
+        }
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = rand::rng();
+        for n in (0..40).chain([100, 1000]) {
+            let mut naive = Vec::from_iter((0..n).map(|_| rng.random_range(-100..100)));
+            let mut max = LazySegtree::<AddMax>::from(naive.clone());
+            let mut sum = LazySegtree::<AddSum>::from(naive.clone());
+
+            for _ in 0..1000 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                if rng.random_bool(0.5) {
+                    let f = rng.random_range(-100..100);
+                    naive[l..r].iter_mut().for_each(|v| *v += f);
+                    max.range_update(l..r, f);
+                    sum.range_update(l..r, f);
+                } else if rng.random_bool(0.5) {
+                    let i = rng.random_range(0..n.max(1));
+                    if i < n {
+                        assert_eq!(max.point_get(i), naive[i]);
+                        assert_eq!(sum.point_get(i), naive[i]);
+
+                        let v = rng.random_range(-100..100);
+                        naive[i] = v;
+                        max.point_set(i, v);
+                        sum.point_set(i, v);
+                    }
+                } else if rng.random_bool(0.5) {
+                    let k = rng.random_range(-200..200);
+                    let pred = |v: i64| v < k;
+                    assert_eq!(
+                        max.partition_right(l, pred),
+                        (l..n).find(|&i| naive[i] >= k).unwrap_or(n)
+                    );
+                    assert_eq!(
+                        max.partition_left(r, pred),
+                        (0..r).rfind(|&i| naive[i] >= k).map_or(0, |i| i + 1)
+                    );
+                } else {
+                    assert_eq!(
+                        max.range_query(l..r),
+                        naive[l..r].iter().copied().max().unwrap_or(i64::MIN)
+                    );
+                    assert_eq!(sum.range_query(l..r), naive[l..r].iter().sum::<i64>());
+                }
+            }
+
+            assert_eq!(max.data(), naive);
+            assert_eq!(sum.data(), naive);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use ops::{marker::Commutative, Monoid};
+use rollback_union_find::RollbackUnionFind;
+use rustc_hash::FxHashMap;
+
+/// 連結性に関するクエリ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Query {
+    /// 2 頂点が連結か
+    Connected(usize, usize),
+    /// 頂点が属する連結成分の頂点数
+    Size(usize),
+    /// 頂点が属する連結成分の値の総和
+    Value(usize),
+}
+
+/// [`Query`]に対する答え
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Answer<S> {
+    Connected(bool),
+    Size(usize),
+    Value(S),
+}
+
+/// 辺の追加・削除と連結性に関するクエリを先読みして処理するデータ構造。
+///
+/// 時間軸上のセグメント木に各辺の生存期間を載せ、[`RollbackUnionFind`]で DFS する。
+/// 操作の総数を *Q* とすると、全体で *O*(*Q* log *Q* log *N*) 時間で処理する。
+///
+/// # Example
+///
+/// ```
+/// use offline_dynamic_connectivity::{Answer, OfflineDynamicConnectivity, Query};
+/// use ops::ops::Additive;
+///
+/// let mut dc = OfflineDynamicConnectivity::<Additive<i64>>::with_values(vec![1, 10, 100]);
+/// dc.add_edge(0, 1);
+/// dc.add_edge(1, 2);
+/// dc.query(Query::Value(0));
+/// dc.remove_edge(0, 1);
+/// dc.query(Query::Connected(0, 2));
+/// dc.query(Query::Size(2));
+///
+/// assert_eq!(
+///     dc.solve(),
+///     [Answer::Value(111), Answer::Connected(false), Answer::Size(2)]
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct OfflineDynamicConnectivity<T>
+where
+    T: Commutative + Monoid<Set: Copy>,
+{
+    values: Vec<T::Set>,
+    /// 存在する辺と、それぞれが追加された時刻。多重辺も区別する
+    alive: FxHashMap<(usize, usize), Vec<usize>>,
+    /// 削除済みの辺と、その生存期間`l..r`。時刻はクエリの番号で表す
+    dead: Vec<(usize, usize, usize, usize)>,
+    queries: Vec<Query>,
+}
+
+impl<T> OfflineDynamicConnectivity<T>
+where
+    T: Commutative + Monoid<Set: Copy>,
+{
+    /// 頂点数`n`で初期化する。各頂点の値は単位元とする。
+    pub fn new(n: usize) -> Self {
+        Self::with_values(vec![T::id(); n])
+    }
+
+    /// 各頂点が値を持つ場合の初期化子。
+    pub fn with_values(values: Vec<T::Set>) -> Self {
+        Self {
+            values,
+            alive: FxHashMap::default(),
+            dead: Vec::new(),
+            queries: Vec::new(),
+        }
+    }
+
+    /// 辺`(u, v)`を追加する。
+    ///
+    /// # Panics
+    ///
+    /// - `u` and `v` should be less than `N`
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(u.max(v) < self.values.len(), "index out of bounds");
+
+        self.alive
+            .entry((u.min(v), u.max(v)))
+            .or_default()
+            .push(self.queries.len());
+    }
+
+    /// 辺`(u, v)`を 1 本削除する。
+    ///
+    /// # Panics
+    ///
+    /// - edge `(u, v)` should exist
+    pub fn remove_edge(&mut self, u: usize, v: usize) {
+        let (u, v) = (u.min(v), u.max(v));
+        let l = self
+            .alive
+            .get_mut(&(u, v))
+            .and_then(Vec::pop)
+            .expect("edge should exist");
+
+        let r = self.queries.len();
+        if l < r {
+            self.dead.push((u, v, l, r));
+        }
+    }
+
+    /// クエリを追加し、その番号を返す。
+    ///
+    /// # Panics
+    ///
+    /// - vertices should be less than `N`
+    pub fn query(&mut self, query: Query) -> usize {
+        let max = match query {
+            Query::Connected(u, v) => u.max(v),
+            Query::Size(x) | Query::Value(x) => x,
+        };
+        assert!(max < self.values.len(), "index out of bounds");
+
+        self.queries.push(query);
+        self.queries.len() - 1
+    }
+
+    /// すべてのクエリに答える。答えはクエリを追加した順に並ぶ。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(*Q* log *Q* log *N*)
+    pub fn solve(self) -> Vec<Answer<T::Set>> {
+        let q = self.queries.len();
+        if q == 0 {
+            return Vec::new();
+        }
+
+        // 時間軸上のセグメント木の各ノードに、その期間を通して存在する辺を載せる
+        let size = q.next_power_of_two();
+        let mut segments = vec![Vec::new(); 2 * size];
+        let alive = self
+            .alive
+            .into_iter()
+            .flat_map(|((u, v), ls)| ls.into_iter().map(move |l| (u, v, l, q)));
+        for (u, v, l, r) in self.dead.into_iter().chain(alive) {
+            let (mut l, mut r) = (l + size, r + size);
+            while l < r {
+                if l & 1 == 1 {
+                    segments[l].push((u, v));
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    segments[r].push((u, v));
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+        }
+
+        let mut dfs = Dfs {
+            segments,
+            queries: self.queries,
+            uf: RollbackUnionFind::<T>::with_values(self.values),
+            answers: Vec::with_capacity(q),
+        };
+        dfs.run(1);
+
+        dfs.answers
+    }
+}
+
+struct Dfs<T>
+where
+    T: Commutative + Monoid<Set: Copy>,
+{
+    segments: Vec<Vec<(usize, usize)>>,
+    queries: Vec<Query>,
+    uf: RollbackUnionFind<T>,
+    answers: Vec<Answer<T::Set>>,
+}
+
+impl<T> Dfs<T>
+where
+    T: Commutative + Monoid<Set: Copy>,
+{
+    fn run(&mut self, node: usize) {
+        let mut merged = 0;
+        for i in 0..self.segments[node].len() {
+            let (u, v) = self.segments[node][i];
+            if self.uf.merge(u, v) {
+                merged += 1
+            }
+        }
+
+        let size = self.segments.len() / 2;
+        if node < size {
+            self.run(2 * node);
+            self.run(2 * node + 1);
+        } else if let Some(&query) = self.queries.get(node - size) {
+            let answer = match query {
+                Query::Connected(u, v) => Answer::Connected(self.uf.same(u, v)),
+                Query::Size(x) => Answer::Size(self.uf.size_value(x).0),
+                Query::Value(x) => Answer::Value(self.uf.size_value(x).1),
+            };
+            self.answers.push(answer);
+        }
+
+        for _ in 0..merged {
+            self.uf.undo();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ops::ops::Additive;
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::rng();
+        for n in 1..20 {
+            let values = Vec::from_iter((0..n).map(|_| rng.random_range(-100..100)));
+            let mut dc = OfflineDynamicConnectivity::<Additive<i64>>::with_values(values.clone());
+            let mut edges = Vec::new();
+            let mut expected = Vec::new();
+
+            for _ in 0..300 {
+                match rng.random_range(0..4) {
+                    0 => {
+                        let (u, v) = (rng.random_range(0..n), rng.random_range(0..n));
+                        edges.push((u, v));
+                        dc.add_edge(u, v);
+                    }
+                    1 if !edges.is_empty() => {
+                        let (u, v) = edges.swap_remove(rng.random_range(0..edges.len()));
+                        // 向きを入れ替えても同じ辺を表す
+                        dc.remove_edge(v, u);
+                    }
+                    _ => {
+                        let mut uf =
+                            RollbackUnionFind::<Additive<i64>>::with_values(values.clone());
+                        for &(u, v) in &edges {
+                            uf.merge(u, v);
+                        }
+
+                        let (x, y) = (rng.random_range(0..n), rng.random_range(0..n));
+                        let (query, answer) = match rng.random_range(0..3) {
+                            0 => (Query::Connected(x, y), Answer::Connected(uf.same(x, y))),
+                            1 => (Query::Size(x), Answer::Size(uf.size_value(x).0)),
+                            _ => (Query::Value(x), Answer::Value(uf.size_value(x).1)),
+                        };
+                        assert_eq!(dc.query(query), expected.len());
+                        expected.push(answer);
+                    }
+                }
+            }
+
+            assert_eq!(dc.solve(), expected);
+        }
+    }
+}
@@ -0,0 +1,222 @@
+/// 部分永続 union-find。過去の任意の時刻における素集合を参照できる。
+///
+/// 初期状態を時刻 0 とし、`k`回目の[`union`](Self::union)を時刻`k`に行われたものとする。
+/// 時刻`t`の状態とは、時刻`t`以前の`union`をすべて行った状態である。
+/// 現在の時刻より後の`t`（`usize::MAX`など）は最新の状態を表す。
+///
+/// # Example
+///
+/// ```
+/// use partially_persistent_union_find::PartiallyPersistentUnionFind;
+///
+/// // 各日に道路が 1 本ずつ開通するとき、2 都市が初めて行き来できるようになる日
+/// let mut uf = PartiallyPersistentUnionFind::new(4);
+/// for (u, v) in [(0, 1), (2, 3), (1, 2)] {
+///     uf.union(u, v);
+/// }
+///
+/// assert_eq!(uf.first_connected_time(0, 3), Some(3));
+/// assert!(!uf.same_at(0, 3, 2));
+/// assert_eq!(uf.size_at(3, 2), 2);
+/// assert_eq!(uf.size_at(3, 3), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PartiallyPersistentUnionFind {
+    /// 親へのポインター。根なら自身を指す。
+    parent: Vec<usize>,
+
+    /// 根でなくなった時刻。根なら`usize::MAX`
+    time: Vec<usize>,
+
+    /// 根である間の要素数の履歴。`(時刻, 要素数)`を時刻の昇順に並べる。
+    size: Vec<Vec<(usize, usize)>>,
+
+    /// 最後に行った`union`の時刻
+    now: usize,
+}
+
+impl PartiallyPersistentUnionFind {
+    /// `n`要素で初期化する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: Vec::from_iter(0..n),
+            time: vec![usize::MAX; n],
+            size: vec![vec![(0, 1)]; n],
+            now: 0,
+        }
+    }
+
+    /// ノード数を返す。
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// 現在の時刻、すなわちこれまでに`union`を呼んだ回数を返す。
+    pub fn now(&self) -> usize {
+        self.now
+    }
+
+    /// 時刻`t`において`x`が所属する素集合の代表元を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `x` should be less than `N`
+    pub fn find_at(&self, mut x: usize, t: usize) -> usize {
+        // 根の`time`は番兵の`usize::MAX`なので、それより小さくしておく
+        let t = t.min(self.now);
+        while self.time[x] <= t {
+            x = self.parent[x];
+        }
+
+        x
+    }
+
+    /// 時刻を 1 進め、`x`と`y`の所属する素集合を結合する。
+    /// すでに同じ集合に属している場合は`false`を、そうでない場合は`true`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `x` and `y` should be less than `N`
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        self.now += 1;
+        let mut x = self.find_at(x, self.now);
+        let mut y = self.find_at(y, self.now);
+        if x == y {
+            return false;
+        }
+
+        // union by size
+        let (x_size, y_size) = (self.size_at(x, self.now), self.size_at(y, self.now));
+        if x_size < y_size {
+            std::mem::swap(&mut x, &mut y);
+        }
+        self.parent[y] = x;
+        self.time[y] = self.now;
+        self.size[x].push((self.now, x_size + y_size));
+
+        true
+    }
+
+    /// 時刻`t`において`x`と`y`が同じ素集合に所属している場合は`true`を、そうでない場合は`false`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `x` and `y` should be less than `N`
+    pub fn same_at(&self, x: usize, y: usize, t: usize) -> bool {
+        self.find_at(x, t) == self.find_at(y, t)
+    }
+
+    /// 時刻`t`において`x`が所属する素集合の要素数を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `x` should be less than `N`
+    pub fn size_at(&self, x: usize, t: usize) -> usize {
+        let history = &self.size[self.find_at(x, t)];
+        history[history.partition_point(|&(s, _)| s <= t) - 1].1
+    }
+
+    /// `x`と`y`が初めて同じ素集合に所属した時刻を返す。現在も異なる素集合に属しているなら`None`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `x` and `y` should be less than `N`
+    pub fn first_connected_time(&self, mut x: usize, mut y: usize) -> Option<usize> {
+        assert!(x.max(y) < self.len(), "index out of bounds");
+
+        // 根に向かう辺の時刻は単調増加なので、時刻の小さい方から辿れば最後に辿った辺が答えとなる
+        let mut t = 0;
+        while x != y {
+            if self.time[x] > self.time[y] {
+                std::mem::swap(&mut x, &mut y);
+            }
+            if self.time[x] == usize::MAX {
+                return None;
+            }
+            t = self.time[x];
+            x = self.parent[x];
+        }
+
+        Some(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::rng();
+        for n in 1..30 {
+            let mut uf = PartiallyPersistentUnionFind::new(n);
+            // 各時刻における各要素の所属する集合の番号
+            let mut history = vec![Vec::from_iter(0..n)];
+            for _ in 0..2 * n {
+                let (x, y) = (rng.random_range(0..n), rng.random_range(0..n));
+                let mut label = history.last().unwrap().clone();
+                let (lx, ly) = (label[x], label[y]);
+                label.iter_mut().filter(|l| **l == ly).for_each(|l| *l = lx);
+                assert_eq!(uf.union(x, y), lx != ly);
+                history.push(label);
+            }
+            assert_eq!(uf.now(), history.len() - 1);
+
+            for _ in 0..1000 {
+                let (x, y) = (rng.random_range(0..n), rng.random_range(0..n));
+                let t = rng.random_range(0..history.len() + 2);
+                let label = &history[t.min(history.len() - 1)];
+                assert_eq!(uf.same_at(x, y, t), label[x] == label[y]);
+                assert_eq!(
+                    uf.size_at(x, t),
+                    label.iter().filter(|&&l| l == label[x]).count()
+                );
+                assert_eq!(
+                    uf.first_connected_time(x, y),
+                    history.iter().position(|label| label[x] == label[y])
+                );
+            }
+
+            // 十分大きな時刻は最新の状態を表す
+            let label = history.last().unwrap();
+            for x in 0..n {
+                assert_eq!(uf.find_at(x, usize::MAX), uf.find_at(x, uf.now()));
+                assert_eq!(uf.same_at(0, x, usize::MAX), label[0] == label[x]);
+                assert_eq!(
+                    uf.size_at(x, usize::MAX),
+                    label.iter().filter(|&&l| l == label[x]).count()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+#[derive(Debug, Clone)]
+enum Node<T> {
+    Leaf(T),
+    Branch([u32; 2]),
+}
+
+/// [`PersistentArray`]のバージョン。内部的にはルートノードを指す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Version(u32);
+
+/// 完全永続配列。経路複製により、一点更新と一点取得を *Θ*(log *N*) 時間で処理する。
+///
+/// 更新のたびに新しい[`Version`]を返し、過去のバージョンはそのまま残る。
+///
+/// # Example
+///
+/// ```
+/// use persistent_array::PersistentArray;
+///
+/// let mut array = PersistentArray::from(vec![1, 2, 3]);
+/// let v0 = array.initial();
+/// let v1 = array.set(v0, 1, 20);
+/// let v2 = array.set(v0, 2, 30);
+///
+/// assert_eq!(array.get(v0, 1), &2);
+/// assert_eq!(array.get(v1, 1), &20);
+/// assert_eq!(array.get(v2, 1), &2);
+/// assert_eq!(array.get(v2, 2), &30);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PersistentArray<T> {
+    nodes: Vec<Node<T>>,
+    len: usize,
+    initial: Version,
+}
+
+impl<T> PersistentArray<T> {
+    /// 初期状態のバージョン
+    pub fn initial(&self) -> Version {
+        self.initial
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn new_node(&mut self, node: Node<T>) -> u32 {
+        let id = u32::try_from(self.nodes.len()).expect("too many nodes");
+        self.nodes.push(node);
+
+        id
+    }
+
+    fn build(&mut self, values: &mut impl Iterator<Item = T>, len: usize) -> u32 {
+        if len == 1 {
+            let value = values.next().unwrap();
+            return self.new_node(Node::Leaf(value));
+        }
+
+        let left = self.build(values, len / 2);
+        let right = self.build(values, len - len / 2);
+        self.new_node(Node::Branch([left, right]))
+    }
+
+    fn update(&mut self, node: u32, [lo, hi]: [usize; 2], i: usize, value: T) -> u32 {
+        let Node::Branch(mut children) = self.nodes[node as usize] else {
+            return self.new_node(Node::Leaf(value));
+        };
+
+        let mid = (lo + hi) / 2;
+        if i < mid {
+            children[0] = self.update(children[0], [lo, mid], i, value)
+        } else {
+            children[1] = self.update(children[1], [mid, hi], i, value)
+        }
+
+        self.new_node(Node::Branch(children))
+    }
+
+    /// `version`の`i`番目の要素を`value`で置き換えた、新しいバージョンを返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*) 時間で、*Θ*(log *N*) 個のノードを追加する。
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn set(&mut self, version: Version, i: usize, value: T) -> Version {
+        assert!(i < self.len, "index out of bounds");
+
+        Version(self.update(version.0, [0, self.len], i, value))
+    }
+
+    /// `version`の`i`番目の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn get(&self, version: Version, i: usize) -> &T {
+        assert!(i < self.len, "index out of bounds");
+
+        let [mut lo, mut hi] = [0, self.len];
+        let mut node = version.0;
+        loop {
+            match &self.nodes[node as usize] {
+                Node::Leaf(value) => return value,
+                Node::Branch(children) => {
+                    let mid = (lo + hi) / 2;
+                    if i < mid {
+                        node = children[0];
+                        hi = mid;
+                    } else {
+                        node = children[1];
+                        lo = mid;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for PersistentArray<T> {
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    fn from(value: Vec<T>) -> Self {
+        let len = value.len();
+        let mut array = Self {
+            nodes: Vec::with_capacity(2 * len),
+            len,
+            initial: Version(0),
+        };
+        if len > 0 {
+            array.initial = Version(array.build(&mut value.into_iter(), len));
+        }
+
+        array
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::rng();
+        for n in (1..20).chain([100]) {
+            let init = Vec::from_iter((0..n).map(|_| rng.random::<u32>()));
+            let mut array = PersistentArray::from(init.clone());
+            let mut history = vec![(array.initial(), init)];
+
+            for _ in 0..1000 {
+                let (version, naive) = &history[rng.random_range(0..history.len())];
+                let i = rng.random_range(0..n);
+                if rng.random_bool(0.3) {
+                    let value = rng.random();
+                    let mut naive = naive.clone();
+                    naive[i] = value;
+                    let version = array.set(*version, i, value);
+                    history.push((version, naive));
+                } else {
+                    assert_eq!(array.get(*version, i), &naive[i]);
+                }
+            }
+
+            for (version, naive) in history {
+                assert_eq!(
+                    Vec::from_iter((0..n).map(|i| *array.get(version, i))),
+                    naive
+                );
+            }
+        }
+    }
+}
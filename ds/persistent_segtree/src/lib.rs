@@ -0,0 +1,593 @@
+use std::ops::{Bound, Range, RangeBounds};
+
+use ops::{marker::Commutative, Group, Monoid};
+
+/// 番兵ノード。空の部分木を表し、単位元を保持する。
+const NIL: u32 = 0;
+
+#[derive(Debug, Clone)]
+struct Node<S> {
+    value: S,
+    children: [u32; 2],
+}
+
+/// [`PersistentSegtree`]のバージョン。内部的にはルートノードを指す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Version(u32);
+
+/// 完全永続な一点更新・区間クエリを処理するデータ構造。
+///
+/// 更新のたびに新しい[`Version`]を返し、過去のバージョンはそのまま残る。
+/// 非可換な演算でも、左から順に畳み込んだ結果を返す。
+#[derive(Debug, Clone)]
+pub struct PersistentSegtree<T>
+where
+    T: Monoid,
+{
+    /// `nodes[0]`は番兵とする。
+    nodes: Vec<Node<T::Set>>,
+    net_len: usize,
+    initial: Version,
+}
+
+impl<T> PersistentSegtree<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    /// 単位元が`n`個並んだ列で初期化する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn new(n: usize) -> Self {
+        Self {
+            nodes: vec![Node {
+                value: T::id(),
+                children: [NIL; 2],
+            }],
+            net_len: n,
+            initial: Version(NIL),
+        }
+    }
+
+    /// 初期状態のバージョン
+    pub fn initial(&self) -> Version {
+        self.initial
+    }
+
+    pub fn len(&self) -> usize {
+        self.net_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.net_len == 0
+    }
+
+    fn parse_range<R>(&self, range: R) -> Range<usize>
+    where
+        R: RangeBounds<usize>,
+    {
+        let l = match range.start_bound() {
+            Bound::Included(&l) => l,
+            Bound::Excluded(&l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(&r) => r + 1,
+            Bound::Excluded(&r) => r,
+            Bound::Unbounded => self.net_len,
+        };
+        assert!(r <= self.net_len, "index out of bounds");
+
+        l..r
+    }
+
+    fn new_node(&mut self, value: T::Set, children: [u32; 2]) -> u32 {
+        let node = u32::try_from(self.nodes.len()).expect("too many nodes");
+        self.nodes.push(Node { value, children });
+
+        node
+    }
+
+    fn build(&mut self, values: &[T::Set]) -> u32 {
+        if let [value] = values {
+            return self.new_node(*value, [NIL; 2]);
+        }
+
+        let (left, right) = values.split_at(values.len() / 2);
+        let children = [self.build(left), self.build(right)];
+        let value = T::op(
+            self.nodes[children[0] as usize].value,
+            self.nodes[children[1] as usize].value,
+        );
+
+        self.new_node(value, children)
+    }
+
+    fn update<F>(&mut self, node: u32, [lo, hi]: [usize; 2], i: usize, f: F) -> u32
+    where
+        F: FnOnce(T::Set) -> T::Set,
+    {
+        if lo + 1 == hi {
+            return self.new_node(f(self.nodes[node as usize].value), [NIL; 2]);
+        }
+
+        let mid = (lo + hi) / 2;
+        let mut children = self.nodes[node as usize].children;
+        if i < mid {
+            children[0] = self.update(children[0], [lo, mid], i, f)
+        } else {
+            children[1] = self.update(children[1], [mid, hi], i, f)
+        }
+        let value = T::op(
+            self.nodes[children[0] as usize].value,
+            self.nodes[children[1] as usize].value,
+        );
+
+        self.new_node(value, children)
+    }
+
+    /// `version`の`i`番目の要素を`f`で更新した、新しいバージョンを返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*) 時間で、*Θ*(log *N*) 個のノードを追加する。
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_update_with<F>(&mut self, version: Version, i: usize, f: F) -> Version
+    where
+        F: FnOnce(T::Set) -> T::Set,
+    {
+        assert!(i < self.net_len, "index out of bounds");
+
+        Version(self.update(version.0, [0, self.net_len], i, f))
+    }
+
+    /// `version`の`i`番目の要素を`value`で置き換えた、新しいバージョンを返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*) 時間で、*Θ*(log *N*) 個のノードを追加する。
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_set(&mut self, version: Version, i: usize, value: T::Set) -> Version {
+        self.point_update_with(version, i, |_| value)
+    }
+
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_get(&self, version: Version, i: usize) -> T::Set {
+        assert!(i < self.net_len, "index out of bounds");
+
+        let [mut lo, mut hi] = [0, self.net_len];
+        let mut node = version.0;
+        while node != NIL && lo + 1 < hi {
+            let mid = (lo + hi) / 2;
+            if i < mid {
+                hi = mid;
+                node = self.nodes[node as usize].children[0];
+            } else {
+                lo = mid;
+                node = self.nodes[node as usize].children[1];
+            }
+        }
+
+        self.nodes[node as usize].value
+    }
+
+    fn fold(&self, node: u32, [lo, hi]: [usize; 2], [l, r]: [usize; 2]) -> T::Set {
+        if node == NIL || r <= lo || hi <= l {
+            return T::id();
+        }
+        if l <= lo && hi <= r {
+            return self.nodes[node as usize].value;
+        }
+
+        let mid = (lo + hi) / 2;
+        let [left, right] = self.nodes[node as usize].children;
+        T::op(
+            self.fold(left, [lo, mid], [l, r]),
+            self.fold(right, [mid, hi], [l, r]),
+        )
+    }
+
+    /// `version`における区間クエリに答える。区間が空なら単位元を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_query<R>(&self, version: Version, range: R) -> T::Set
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = self.parse_range(range);
+        if start >= end {
+            return T::id();
+        }
+
+        self.fold(version.0, [0, self.net_len], [start, end])
+    }
+
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn range_full_query(&self, version: Version) -> T::Set {
+        self.nodes[version.0 as usize].value
+    }
+
+    /// 各ノードの値を`value(node)`として、`pred(l..r)`が`true`となる最大の`r`を探す。
+    fn max_right<V, F>(
+        &self,
+        value: &V,
+        nodes: [u32; 2],
+        [lo, hi]: [usize; 2],
+        l: usize,
+        pred: &mut F,
+        acc: &mut T::Set,
+    ) -> Option<usize>
+    where
+        V: Fn([u32; 2]) -> T::Set,
+        F: FnMut(T::Set) -> bool,
+    {
+        if hi <= l {
+            return None;
+        }
+        if l <= lo {
+            let temp = T::op(*acc, value(nodes));
+            if pred(temp) {
+                *acc = temp;
+                return None;
+            }
+            if lo + 1 == hi {
+                return Some(lo);
+            }
+        }
+
+        let mid = (lo + hi) / 2;
+        let [left, right] =
+            [0, 1].map(|dir| nodes.map(|node| self.nodes[node as usize].children[dir]));
+        self.max_right(value, left, [lo, mid], l, pred, acc)
+            .or_else(|| self.max_right(value, right, [mid, hi], l, pred, acc))
+    }
+
+    /// 各ノードの値を`value(node)`として、`pred(l..r)`が`true`となる最小の`l`を探す。
+    fn min_left<V, F>(
+        &self,
+        value: &V,
+        nodes: [u32; 2],
+        [lo, hi]: [usize; 2],
+        r: usize,
+        pred: &mut F,
+        acc: &mut T::Set,
+    ) -> Option<usize>
+    where
+        V: Fn([u32; 2]) -> T::Set,
+        F: FnMut(T::Set) -> bool,
+    {
+        if r <= lo {
+            return None;
+        }
+        if hi <= r {
+            let temp = T::op(value(nodes), *acc);
+            if pred(temp) {
+                *acc = temp;
+                return None;
+            }
+            if lo + 1 == hi {
+                return Some(hi);
+            }
+        }
+
+        let mid = (lo + hi) / 2;
+        let [left, right] =
+            [0, 1].map(|dir| nodes.map(|node| self.nodes[node as usize].children[dir]));
+        self.min_left(value, right, [mid, hi], r, pred, acc)
+            .or_else(|| self.min_left(value, left, [lo, mid], r, pred, acc))
+    }
+
+    /// `version`において`pred(l..r)`が`true`となる最大の`r`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `l` should be less than or equal to `N`
+    pub fn partition_right<F>(&self, version: Version, l: usize, mut pred: F) -> usize
+    where
+        F: FnMut(T::Set) -> bool,
+    {
+        assert!(l <= self.net_len, "index out of bounds");
+
+        let value = |[node, _]: [u32; 2]| self.nodes[node as usize].value;
+        self.max_right(
+            &value,
+            [version.0; 2],
+            [0, self.net_len],
+            l,
+            &mut pred,
+            &mut T::id(),
+        )
+        .unwrap_or(self.net_len)
+    }
+
+    /// `version`において`pred(l..r)`が`true`となる最小の`l`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `r` should be less than or equal to `N`
+    pub fn partition_left<F>(&self, version: Version, r: usize, mut pred: F) -> usize
+    where
+        F: FnMut(T::Set) -> bool,
+    {
+        assert!(r <= self.net_len, "index out of bounds");
+
+        let value = |[node, _]: [u32; 2]| self.nodes[node as usize].value;
+        self.min_left(
+            &value,
+            [version.0; 2],
+            [0, self.net_len],
+            r,
+            &mut pred,
+            &mut T::id(),
+        )
+        .unwrap_or(0)
+    }
+
+    /// バージョン間の差分`new - old`について、`pred(l..r)`が`true`となる最大の`r`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
+    /// 値の出現回数を数えるセグメント木に対して、区間内で`k`番目に小さい値を求めるのに利用できる。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `l` should be less than or equal to `N`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ops::ops::Additive;
+    /// use persistent_segtree::PersistentSegtree;
+    ///
+    /// let a = [3, 1, 4, 1, 5, 9, 2, 6];
+    ///
+    /// // versions[i] は a[..i] に含まれる値の出現回数
+    /// let mut segtree = PersistentSegtree::<Additive<i32>>::new(10);
+    /// let mut versions = vec![segtree.initial()];
+    /// for &x in &a {
+    ///     let v = segtree.point_update_with(*versions.last().unwrap(), x, |c| c + 1);
+    ///     versions.push(v);
+    /// }
+    ///
+    /// // a[2..6] = [4, 1, 5, 9] の中で 2 番目 (0-indexed) に小さい値
+    /// let x = segtree.partition_right_diff(versions[2], versions[6], 0, |c| c <= 2);
+    /// assert_eq!(x, 5);
+    /// ```
+    pub fn partition_right_diff<F>(
+        &self,
+        old: Version,
+        new: Version,
+        l: usize,
+        mut pred: F,
+    ) -> usize
+    where
+        T: Group + Commutative,
+        F: FnMut(T::Set) -> bool,
+    {
+        assert!(l <= self.net_len, "index out of bounds");
+
+        let value = |[old, new]: [u32; 2]| {
+            T::op(
+                T::inv(self.nodes[old as usize].value),
+                self.nodes[new as usize].value,
+            )
+        };
+        self.max_right(
+            &value,
+            [old.0, new.0],
+            [0, self.net_len],
+            l,
+            &mut pred,
+            &mut T::id(),
+        )
+        .unwrap_or(self.net_len)
+    }
+
+    /// バージョン間の差分`new - old`について、`pred(l..r)`が`true`となる最小の`l`を返す。
+    /// `pred`は単調であり、`pred(id) = true`を満たす必要がある。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `r` should be less than or equal to `N`
+    pub fn partition_left_diff<F>(&self, old: Version, new: Version, r: usize, mut pred: F) -> usize
+    where
+        T: Group + Commutative,
+        F: FnMut(T::Set) -> bool,
+    {
+        assert!(r <= self.net_len, "index out of bounds");
+
+        let value = |[old, new]: [u32; 2]| {
+            T::op(
+                T::inv(self.nodes[old as usize].value),
+                self.nodes[new as usize].value,
+            )
+        };
+        self.min_left(
+            &value,
+            [old.0, new.0],
+            [0, self.net_len],
+            r,
+            &mut pred,
+            &mut T::id(),
+        )
+        .unwrap_or(0)
+    }
+}
+
+impl<T> From<Vec<T::Set>> for PersistentSegtree<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    fn from(value: Vec<T::Set>) -> Self {
+        let mut segtree = Self::new(value.len());
+        segtree.nodes.reserve(2 * value.len());
+        if !value.is_empty() {
+            segtree.initial = Version(segtree.build(&value));
+        }
+
+        segtree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mint::Mint;
+    use ops::{
+        ops::{Additive, Affine},
+        Identity, SemiGroup,
+    };
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn random() {
+        type M = Mint<998244353>;
+        type F = Affine<M>;
+
+        let mut rng = rand::rng();
+        let mut gen = || (M::new(rng.random()), M::new(rng.random()));
+        for n in (1..20).chain([100]) {
+            let init = Vec::from_iter((0..n).map(|_| gen()));
+            let mut segtree = PersistentSegtree::<F>::from(init.clone());
+            let mut history = vec![(segtree.initial(), init)];
+
+            let mut rng = rand::rng();
+            for _ in 0..1000 {
+                let (version, naive) = &history[rng.random_range(0..history.len())];
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                if rng.random_bool(0.3) {
+                    let i = rng.random_range(0..n);
+                    let f = (M::new(rng.random()), M::new(rng.random()));
+                    let mut naive = naive.clone();
+                    naive[i] = f;
+                    let version = segtree.point_set(*version, i, f);
+                    history.push((version, naive));
+                } else {
+                    assert_eq!(
+                        segtree.range_query(*version, l..r),
+                        naive[l..r].iter().fold(F::id(), |acc, &f| F::op(acc, f))
+                    );
+                    if l < n {
+                        assert_eq!(segtree.point_get(*version, l), naive[l]);
+                    }
+                }
+            }
+
+            for (version, naive) in history {
+                assert_eq!(
+                    segtree.range_full_query(version),
+                    naive.iter().fold(F::id(), |acc, &f| F::op(acc, f))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn kth_smallest() {
+        let mut rng = rand::rng();
+        for n in 1..50 {
+            let a = Vec::from_iter((0..n).map(|_| rng.random_range(0..20)));
+
+            // versions[i] は a[..i] に含まれる値の出現回数
+            let mut segtree = PersistentSegtree::<Additive<i32>>::new(20);
+            let mut versions = vec![segtree.initial()];
+            for &x in &a {
+                let version = segtree.point_update_with(*versions.last().unwrap(), x, |c| c + 1);
+                versions.push(version);
+            }
+
+            for l in 0..n {
+                for r in l + 1..=n {
+                    let mut sorted = a[l..r].to_vec();
+                    sorted.sort_unstable();
+                    for (k, &x) in sorted.iter().enumerate() {
+                        let k = k as i32;
+                        assert_eq!(
+                            segtree.partition_right_diff(versions[l], versions[r], 0, |c| c <= k),
+                            x
+                        );
+                    }
+
+                    // 大きい方から数える
+                    for (k, &x) in sorted.iter().rev().enumerate() {
+                        let k = k as i32;
+                        assert_eq!(
+                            segtree.partition_left_diff(versions[l], versions[r], 20, |c| c <= k),
+                            x + 1
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn partition() {
+        let mut rng = rand::rng();
+        for n in 0..30 {
+            let a = Vec::from_iter((0..n).map(|_| rng.random_range(0..10)));
+            let segtree = PersistentSegtree::<Additive<i32>>::from(a.clone());
+            let version = segtree.initial();
+
+            for l in 0..=n {
+                for k in 0..30 {
+                    assert_eq!(
+                        segtree.partition_right(version, l, |s| s <= k),
+                        (l..n)
+                            .find(|&r| a[l..=r].iter().sum::<i32>() > k)
+                            .unwrap_or(n)
+                    );
+                    assert_eq!(
+                        segtree.partition_left(version, l, |s| s <= k),
+                        (0..l)
+                            .rfind(|&i| a[i..l].iter().sum::<i32>() > k)
+                            .map_or(0, |i| i + 1)
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use persistent_array::PersistentArray;
+
+pub use persistent_array::Version;
+
+/// 完全永続 union-find。任意のバージョンから分岐して結合できる。
+///
+/// union by size のみを行い、経路圧縮は行わない。
+///
+/// # Example
+///
+/// ```
+/// use persistent_union_find::PersistentUnionFind;
+///
+/// let mut uf = PersistentUnionFind::new(4);
+/// let v0 = uf.initial();
+/// let v1 = uf.union(v0, 0, 1);
+/// let v2 = uf.union(v1, 2, 3);
+/// // v1 から分岐する
+/// let v3 = uf.union(v1, 1, 2);
+///
+/// assert!(uf.same(v2, 0, 1));
+/// assert!(!uf.same(v2, 1, 2));
+/// assert!(uf.same(v3, 0, 2));
+/// assert!(!uf.same(v3, 0, 3));
+/// assert_eq!(uf.size(v3, 2), 3);
+/// assert_eq!(uf.union(v3, 0, 2), v3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PersistentUnionFind {
+    /// 非負なら親へのポインター、負なら要素数を表す。
+    parent_or_size: PersistentArray<i32>,
+}
+
+impl PersistentUnionFind {
+    /// `n`要素で初期化する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent_or_size: PersistentArray::from(vec![-1; n]),
+        }
+    }
+
+    /// 初期状態のバージョン
+    pub fn initial(&self) -> Version {
+        self.parent_or_size.initial()
+    }
+
+    /// ノード数を返す。
+    pub fn len(&self) -> usize {
+        self.parent_or_size.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent_or_size.is_empty()
+    }
+
+    /// `version`において`x`が所属する素集合の代表元のインデックスを返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log² *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `x` should be less than `N`
+    pub fn find(&self, version: Version, mut x: usize) -> usize {
+        loop {
+            let p = *self.parent_or_size.get(version, x);
+            if p.is_negative() {
+                return x;
+            }
+            x = p as usize;
+        }
+    }
+
+    /// `version`において`x`と`y`の所属する素集合を結合した、新しいバージョンを返す。
+    /// すでに同じ集合に属している場合は`version`をそのまま返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log² *N*) 時間で、*Θ*(log *N*) 個のノードを追加する。
+    ///
+    /// # Panics
+    ///
+    /// - `x` and `y` should be less than `N`
+    pub fn union(&mut self, version: Version, x: usize, y: usize) -> Version {
+        let mut x = self.find(version, x);
+        let mut y = self.find(version, y);
+        if x == y {
+            return version;
+        }
+
+        // union by size
+        let mut sx = *self.parent_or_size.get(version, x);
+        let mut sy = *self.parent_or_size.get(version, y);
+        if sx > sy {
+            std::mem::swap(&mut x, &mut y);
+            std::mem::swap(&mut sx, &mut sy);
+        }
+        let version = self.parent_or_size.set(version, x, sx + sy);
+        self.parent_or_size.set(version, y, x as i32)
+    }
+
+    /// `version`において`x`と`y`が同じ素集合に所属している場合は`true`を、そうでない場合は`false`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log² *N*)
+    pub fn same(&self, version: Version, x: usize, y: usize) -> bool {
+        self.find(version, x) == self.find(version, y)
+    }
+
+    /// `version`において`x`が所属する素集合の要素数を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log² *N*)
+    pub fn size(&self, version: Version, x: usize) -> usize {
+        let x = self.find(version, x);
+        -*self.parent_or_size.get(version, x) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::rng();
+        for n in 1..30 {
+            let mut uf = PersistentUnionFind::new(n);
+            // 各バージョンにおける各要素の所属する集合の番号
+            let mut history = vec![(uf.initial(), Vec::from_iter(0..n))];
+
+            for _ in 0..500 {
+                let (version, label) = &history[rng.random_range(0..history.len())];
+                let (x, y) = (rng.random_range(0..n), rng.random_range(0..n));
+                if rng.random_bool(0.3) {
+                    let (lx, ly) = (label[x], label[y]);
+                    let mut label = label.clone();
+                    label.iter_mut().filter(|l| **l == ly).for_each(|l| *l = lx);
+                    let new = uf.union(*version, x, y);
+                    assert_eq!(new == *version, lx == ly);
+                    history.push((new, label));
+                } else {
+                    assert_eq!(uf.same(*version, x, y), label[x] == label[y]);
+                    assert_eq!(
+                        uf.size(*version, x),
+                        label.iter().filter(|&&l| l == label[x]).count()
+                    );
+                    assert_eq!(label[uf.find(*version, x)], label[x]);
+                }
+            }
+        }
+    }
+}
@@ -14,7 +14,7 @@ impl<T, const D: usize> NDArray<T, D> {
         let n = len
             .iter()
             .try_fold(1_usize, |prod, l| prod.checked_mul(*l))
-            .expect("msg");
+            .expect("shape overflows usize");
 
         Self {
             data: vec![value; n].into_boxed_slice(),
@@ -22,6 +22,41 @@ impl<T, const D: usize> NDArray<T, D> {
         }
     }
 
+    /// 多重添え字`[i_0, i_1, ...]`に対して`f`を呼び出した結果で初期化する。
+    pub fn from_fn<F>(len: [usize; D], mut f: F) -> Self
+    where
+        F: FnMut([usize; D]) -> T,
+    {
+        let n = len
+            .iter()
+            .try_fold(1_usize, |prod, l| prod.checked_mul(*l))
+            .expect("shape overflows usize");
+
+        let mut data = Vec::with_capacity(n);
+        let mut index = [0; D];
+        for _ in 0..n {
+            data.push(f(index));
+            // 最後の次元から繰り上げる
+            for (i, l) in index.iter_mut().zip(len).rev() {
+                *i += 1;
+                if *i < l {
+                    break;
+                }
+                *i = 0
+            }
+        }
+
+        Self {
+            data: data.into_boxed_slice(),
+            len,
+        }
+    }
+
+    /// 各次元の長さを返す。
+    pub fn shape(&self) -> [usize; D] {
+        self.len
+    }
+
     pub fn fill(&mut self, value: T)
     where
         T: Clone,
@@ -45,7 +80,7 @@ impl<T, const D: usize> Index<[usize; D]> for NDArray<T, D> {
             .iter()
             .zip(index)
             .try_fold(0_usize, |acc, (l, i)| acc.checked_mul(*l)?.checked_add(i))
-            .expect("msg");
+            .expect("index overflows usize");
 
         &self.data[i]
     }
@@ -58,7 +93,7 @@ impl<T, const D: usize> IndexMut<[usize; D]> for NDArray<T, D> {
             .iter()
             .zip(index)
             .try_fold(0_usize, |acc, (l, i)| acc.checked_mul(*l)?.checked_add(i))
-            .expect("msg");
+            .expect("index overflows usize");
 
         &mut self.data[i]
     }
@@ -73,3 +108,34 @@ impl<T, const D: usize> IntoIterator for NDArray<T, D> {
         <Box<[T]> as IntoIterator>::into_iter(data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_fn() {
+        let a = NDArray::from_fn([2, 3, 4], |[i, j, k]| 100 * i + 10 * j + k);
+        assert_eq!(a.shape(), [2, 3, 4]);
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..4 {
+                    assert_eq!(a[[i, j, k]], 100 * i + 10 * j + k);
+                }
+            }
+        }
+
+        let empty = NDArray::from_fn([3, 0], |_| -> u32 { unreachable!() });
+        assert_eq!(empty.shape(), [3, 0]);
+
+        let b = NDArray::repeat([4, 5], 7);
+        assert_eq!(b.shape(), [4, 5]);
+        assert_eq!(b[[3, 4]], 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "shape overflows usize")]
+    fn overflow() {
+        NDArray::repeat([usize::MAX, 2], 0_u8);
+    }
+}
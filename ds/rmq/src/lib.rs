@@ -1,14 +1,73 @@
-use std::{fmt::Debug, ops::Range, usize};
+use std::{
+    fmt::Debug,
+    ops::{Bound, RangeBounds},
+};
 
-use ops::ops::Min;
 use rustc_hash::FxHashMap;
-use sparse_table::SparseTable;
 
-#[derive(Debug, Clone)]
-pub struct RMQ<T: Ord> {
+/// 狭義弱順序による比較
+pub trait Compare<T> {
+    /// `a`が`b`より真に小さいときに限り`true`を返す。
+    fn less(&self, a: &T, b: &T) -> bool;
+}
+
+impl<T, F> Compare<T> for F
+where
+    F: Fn(&T, &T) -> bool,
+{
+    #[inline]
+    fn less(&self, a: &T, b: &T) -> bool {
+        self(a, b)
+    }
+}
+
+/// [`Ord`]による昇順
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Less;
+
+impl<T: Ord> Compare<T> for Less {
+    #[inline]
+    fn less(&self, a: &T, b: &T) -> bool {
+        a < b
+    }
+}
+
+/// [`Ord`]による降順
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Greater;
+
+impl<T: Ord> Compare<T> for Greater {
+    #[inline]
+    fn less(&self, a: &T, b: &T) -> bool {
+        a > b
+    }
+}
+
+/// 区間の最小値とその位置を定数時間で返すデータ構造。
+///
+/// 比較関数`less`を与えると、`less`の意味で最小の要素を返す。既定では[`Less`]、すなわち[`Ord`]の意味で最小の要素を返す。
+/// 最小値が複数あるときは、最も左の位置を返す。最大値が必要なら[`MaxRMQ`]を使う。
+///
+/// # Example
+///
+/// ```
+/// use rmq::RMQ;
+///
+/// let a = vec![3, 1, 4, 1, 5, 9, 2, 6];
+///
+/// let min = RMQ::from(a.clone());
+/// assert_eq!(min.argmin(2..), Some(3));
+/// assert_eq!(min.query(4..=6), Some(&2));
+///
+/// let by_key = RMQ::by_key(a, |&v| (v as i32 - 5).abs());
+/// assert_eq!(by_key.query(..), Some(&5));
+/// ```
+#[derive(Clone)]
+pub struct RMQ<T, C = Less> {
     data: Box<[T]>,
-    /// 各ブロックの最小値からなる Sparse Table
-    summary_rmq: SparseTable<Min<T>>,
+    less: C,
+    /// 各ブロックの最小値の位置からなる Sparse Table
+    summary_rmq: IndexSparseTable,
     block_size: usize,
     /// 出現したブロックレベルの RMQ の一覧
     block_rmq: Box<[BlockRMQ]>,
@@ -16,45 +75,56 @@ pub struct RMQ<T: Ord> {
     block_to_rmq: Box<[usize]>,
 }
 
-impl<T: Ord + Copy> RMQ<T> {
+impl<T: Debug, C> Debug for RMQ<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RMQ")
+            .field("data", &self.data)
+            .field("summary_rmq", &self.summary_rmq)
+            .field("block_size", &self.block_size)
+            .field("block_rmq", &self.block_rmq)
+            .field("block_to_rmq", &self.block_to_rmq)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> RMQ<T> {
+    /// `key`の値が最小となる要素を返す RMQ を構築する。
+    ///
     /// # Time Complexity
     ///
-    /// *O*(1)
-    pub fn query(&self, range: Range<usize>) -> T {
-        let (div_s, rem_s) = (range.start / self.block_size, range.start % self.block_size);
-        let (div_e, rem_e) = (range.end / self.block_size, range.end % self.block_size);
-
-        // block_size が usize::MAX のとき、div_s = div_e = 0 が成り立つ。
-        if div_s == div_e {
-            let i = self.block_rmq[self.block_to_rmq[div_s]].position_by_min(rem_s..rem_e);
-            return self.data[self.block_size * div_s + i];
-        }
-
-        // div_s < div_e より、start に対応するブロックのサイズは block_size である
-        let mut min = {
-            let i =
-                self.block_rmq[self.block_to_rmq[div_s]].position_by_min(rem_s..self.block_size);
-            self.data[self.block_size * div_s + i]
-        };
-        if rem_e != 0 {
-            min = min.min({
-                let i = self.block_rmq[self.block_to_rmq[div_e]].position_by_min(0..rem_e);
-                self.data[self.block_size * div_e + i]
-            });
-        }
-        if let Some(v) = self.summary_rmq.range_query(div_s + 1..div_e) {
-            min = min.min(v)
-        }
+    /// *Θ*(*N*)
+    pub fn by_key<K, U>(values: Vec<T>, key: K) -> RMQ<T, impl Fn(&T, &T) -> bool>
+    where
+        K: Fn(&T) -> U,
+        U: Ord,
+    {
+        RMQ::with_comparator(values, move |a, b| key(a) < key(b))
+    }
+}
 
-        min
+impl<T, F> RMQ<T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    /// 比較関数`less`の意味で最小の要素を返す RMQ を構築する。
+    /// `less(a, b)`は`a`が`b`より真に小さいときに限り`true`を返す必要がある（狭義弱順序）。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn with_comparator(values: Vec<T>, less: F) -> Self {
+        Self::build(values, less)
     }
 }
 
-impl<T: Ord + Copy> From<Vec<T>> for RMQ<T> {
+impl<T, C> RMQ<T, C>
+where
+    C: Compare<T>,
+{
     /// # Time Complexity
     ///
     /// *Θ*(*N*)
-    fn from(values: Vec<T>) -> Self {
+    fn build(values: Vec<T>, less: C) -> Self {
         // Vec のサイズは高々 usize::MAX/2 なので、オーバーフローしない
         let block_size = if values.len() < 1 << 4 {
             // 要素数が小さい場合、ブロックサイズが 0 になり、 panic してしまう。
@@ -68,16 +138,16 @@ impl<T: Ord + Copy> From<Vec<T>> for RMQ<T> {
         let mut block_to_rmq = Vec::with_capacity(block_num);
 
         // ブロックごとに Cartesian Tree を計算し、ブロックレベルのRMQを構築する。
-        // ブロックごとの最小値を summary に記録する。
+        // ブロックごとの最小値の位置を summary に記録する。
         let mut stack = Vec::new();
         let mut cartesian_tree = FxHashMap::default();
         cartesian_tree.reserve(block_num);
         for block in values.chunks(block_size) {
             // block.len() < usize::BIT/2 より、Cartesian tree を usize にエンコードできる
             let mut cartesian_tree_type = 0;
-            for &v in block {
+            for v in block {
                 let mut num_pop = 0;
-                while stack.pop_if(|u| *u > v).is_some() {
+                while stack.pop_if(|u| less.less(v, *u)).is_some() {
                     num_pop += 1
                 }
                 stack.push(v);
@@ -96,22 +166,254 @@ impl<T: Ord + Copy> From<Vec<T>> for RMQ<T> {
             // 新しく Cartesian Tree を計算する
             block_to_rmq.push(block_rmq.len());
             cartesian_tree.insert(cartesian_tree_type, block_rmq.len());
-            block_rmq.push(BlockRMQ::new(block));
+            block_rmq.push(BlockRMQ::new(block, |a, b| less.less(a, b)));
         }
 
-        for (i, block) in values.chunks(block_size).enumerate() {
-            let j = block_rmq[block_to_rmq[i]].position_by_min(0..block.len());
-            stack.push(block[j]);
-        }
+        let summary = Vec::from_iter(values.chunks(block_size).enumerate().map(|(i, block)| {
+            block_size * i + block_rmq[block_to_rmq[i]].position_by_min(0..block.len())
+        }));
+        let summary_rmq = IndexSparseTable::new(summary, |i, j| less.less(&values[i], &values[j]));
 
         Self {
             data: values.into_boxed_slice(),
-            summary_rmq: SparseTable::from(stack),
+            less,
+            summary_rmq,
             block_size,
             block_rmq: block_rmq.into_boxed_slice(),
             block_to_rmq: block_to_rmq.into_boxed_slice(),
         }
     }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// `i`と`j`のうち、最小値の位置を返す。等しければ`i`を返す。
+    #[inline]
+    fn select(&self, i: usize, j: usize) -> usize {
+        if self.less.less(&self.data[j], &self.data[i]) {
+            j
+        } else {
+            i
+        }
+    }
+
+    /// 区間の最小値の位置を返す。最小値が複数あるときは最も左の位置を返す。区間が空なら`None`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn argmin<R>(&self, range: R) -> Option<usize>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(l) => *l,
+            Bound::Excluded(l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(r) => r + 1,
+            Bound::Excluded(r) => *r,
+            Bound::Unbounded => self.data.len(),
+        };
+        assert!(end <= self.data.len(), "index out of bounds");
+        if start >= end {
+            return None;
+        }
+
+        let (div_s, rem_s) = (start / self.block_size, start % self.block_size);
+        let (div_e, rem_e) = (end / self.block_size, end % self.block_size);
+
+        // block_size が usize::MAX のとき、div_s = div_e = 0 が成り立つ。
+        if div_s == div_e {
+            let i = self.block_rmq[self.block_to_rmq[div_s]].position_by_min(rem_s..rem_e);
+            return Some(self.block_size * div_s + i);
+        }
+
+        // div_s < div_e より、start に対応するブロックのサイズは block_size である
+        let mut argmin = {
+            let i =
+                self.block_rmq[self.block_to_rmq[div_s]].position_by_min(rem_s..self.block_size);
+            self.block_size * div_s + i
+        };
+        if let Some(i) = self
+            .summary_rmq
+            .range_query(div_s + 1..div_e, |i, j| self.select(i, j))
+        {
+            argmin = self.select(argmin, i)
+        }
+        if rem_e != 0 {
+            let i = self.block_rmq[self.block_to_rmq[div_e]].position_by_min(0..rem_e);
+            argmin = self.select(argmin, self.block_size * div_e + i)
+        }
+
+        Some(argmin)
+    }
+
+    /// 区間の最小値を返す。区間が空なら`None`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn query<R>(&self, range: R) -> Option<&T>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.argmin(range).map(|i| &self.data[i])
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for RMQ<T> {
+    /// 区間の最小値を返す RMQ を構築する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    fn from(values: Vec<T>) -> Self {
+        Self::build(values, Less)
+    }
+}
+
+/// 区間の最大値とその位置を定数時間で返すデータ構造。
+/// 最大値が複数あるときは、最も左の位置を返す。
+///
+/// # Example
+///
+/// ```
+/// use rmq::MaxRMQ;
+///
+/// let max = MaxRMQ::from(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+/// assert_eq!(max.argmax(..5), Some(4));
+/// assert_eq!(max.max(6..), Some(&6));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MaxRMQ<T> {
+    inner: RMQ<T, Greater>,
+}
+
+impl<T: Ord> MaxRMQ<T> {
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// 区間の最大値の位置を返す。最大値が複数あるときは最も左の位置を返す。区間が空なら`None`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn argmax<R>(&self, range: R) -> Option<usize>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.inner.argmin(range)
+    }
+
+    /// 区間の最大値を返す。区間が空なら`None`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn max<R>(&self, range: R) -> Option<&T>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.inner.query(range)
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for MaxRMQ<T> {
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    fn from(values: Vec<T>) -> Self {
+        Self {
+            inner: RMQ::build(values, Greater),
+        }
+    }
+}
+
+/// 位置を値とする Sparse Table。比較関数は呼び出し側が与える。
+#[derive(Debug, Clone)]
+struct IndexSparseTable {
+    table: Box<[usize]>,
+    partition: Box<[usize]>,
+}
+
+impl IndexSparseTable {
+    /// `less(i, j)`は`i`番目の要素が`j`番目の要素より真に小さいときに限り`true`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N* log *N*)
+    fn new(mut value: Vec<usize>, less: impl Fn(usize, usize) -> bool) -> Self {
+        let mut partition = vec![0, value.len()];
+        if value.is_empty() {
+            return Self {
+                table: value.into_boxed_slice(),
+                partition: partition.into_boxed_slice(),
+            };
+        }
+
+        let height = value.len().ilog2() as usize + 1;
+        value.reserve(height * value.len());
+        for i in 0..height {
+            let half = 1 << i;
+            for j in (partition[i]..partition[i + 1]).skip(half) {
+                let (x, y) = (value[j - half], value[j]);
+                value.push(if less(y, x) { y } else { x });
+            }
+            partition.push(value.len());
+        }
+
+        Self {
+            table: value.into_boxed_slice(),
+            partition: partition.into_boxed_slice(),
+        }
+    }
+
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    fn range_query(
+        &self,
+        range: std::ops::Range<usize>,
+        select: impl Fn(usize, usize) -> usize,
+    ) -> Option<usize> {
+        if range.is_empty() {
+            return None;
+        }
+
+        let w = range.len().ilog2() as usize;
+        Some(select(
+            self.table[self.partition[w] + range.start],
+            self.table[self.partition[w] + range.end - (1 << w)],
+        ))
+    }
 }
 
 /// Cartesian Tree に対して RMQ を求めたときに、最小値を与えるインデックスを計算する
@@ -125,14 +427,14 @@ impl BlockRMQ {
     /// # Time Complexity
     ///
     /// *O*(*N*^2)
-    fn new<T: Ord>(block: &[T]) -> Self {
+    fn new<T>(block: &[T], less: impl Fn(&T, &T) -> bool) -> Self {
         let n = block.len();
         let mut pos_rmq = vec![!0; n * n].into_boxed_slice();
         let mut offset = 0;
         for mut i in 0..n {
             pos_rmq[offset + i] = i;
             for j in i + 1..n {
-                if block[j] < block[i] {
+                if less(&block[j], &block[i]) {
                     i = j
                 }
                 pos_rmq[offset + j] = i
@@ -151,7 +453,7 @@ impl BlockRMQ {
     /// # Time Complexity
     ///
     /// *O*(1)
-    fn position_by_min(&self, range: Range<usize>) -> usize {
+    fn position_by_min(&self, range: std::ops::Range<usize>) -> usize {
         assert!(!range.is_empty());
         self.pos_rmq[range.start * self.block_size + range.end - 1]
     }
@@ -161,7 +463,7 @@ impl BlockRMQ {
 mod tests {
     use rand::{rng, Rng};
 
-    use super::{BlockRMQ, RMQ};
+    use super::{BlockRMQ, MaxRMQ, RMQ};
 
     fn randomized_vec(n: usize) -> Vec<usize> {
         let mut res = Vec::from_iter(0..n);
@@ -180,7 +482,7 @@ mod tests {
         let n = 1 << 10;
         for _ in 0..20 {
             let data = randomized_vec(n);
-            let block_rmq = BlockRMQ::new(&data);
+            let block_rmq = BlockRMQ::new(&data, |a, b| a < b);
             for i in 0..n {
                 let mut k = i;
                 for j in i + 1..n {
@@ -205,9 +507,46 @@ mod tests {
                     if data[j - 1] < data[k] {
                         k = j - 1
                     }
-                    assert_eq!(rmq.query(i..j), data[k], "{:?} {}", i..j, data[j - 1])
+                    assert_eq!(
+                        rmq.query(i..j),
+                        Some(&data[k]),
+                        "{:?} {}",
+                        i..j,
+                        data[j - 1]
+                    )
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn argmin_leftmost() {
+        let mut rng = rng();
+        for n in (0..40).chain([100, 1000]) {
+            // 重複を多く含む
+            let data = Vec::from_iter((0..n).map(|_| rng.random_range(0..5)));
+            let min = RMQ::from(data.clone());
+            let max = MaxRMQ::from(data.clone());
+            let by_key = RMQ::by_key(data.clone(), |&v| (v as i32 - 2).abs());
+            assert_eq!(min.len(), n);
+            assert_eq!(max.len(), n);
+
+            for _ in 0..1000 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                let leftmost = |key: &dyn Fn(&u32) -> i32| {
+                    (l..r).reduce(|i, j| if key(&data[j]) < key(&data[i]) { j } else { i })
+                };
+
+                assert_eq!(min.argmin(l..r), leftmost(&|&v| v as i32));
+                assert_eq!(max.argmax(l..r), leftmost(&|&v| -(v as i32)));
+                assert_eq!(max.max(l..r), data[l..r].iter().max());
+                assert_eq!(by_key.argmin(l..r), leftmost(&|&v| (v as i32 - 2).abs()));
+                if r > 0 {
+                    assert_eq!(min.argmin(l..=r - 1), min.argmin(l..r));
                 }
             }
+            assert_eq!(min.argmin(..), min.argmin(0..n));
         }
     }
 }
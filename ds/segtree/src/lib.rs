@@ -2,6 +2,9 @@ use std::ops::{Index, RangeBounds};
 
 use ops::Monoid;
 
+/// 一点更新・区間クエリを処理するデータ構造。
+///
+/// 非可換な演算でも、左から順に畳み込んだ結果を返す。
 #[derive(Debug, Clone)]
 pub struct Segtree<T>
 where
@@ -91,6 +94,10 @@ where
         F: FnMut(T::Set) -> bool,
     {
         // verified with <https://atcoder.jp/contests/practice2/tasks/practice2_j>
+        if l == self.net_len {
+            return self.net_len;
+        }
+
         l += self.offset;
         l >>= l.trailing_zeros();
 
@@ -112,17 +119,14 @@ where
         }
 
         // go down
-        while {
+        while l < self.offset {
             l <<= 1;
-            l < self.data.len()
-        } {
             temp = T::op(acc, self.data[l]);
             if pred(temp) {
                 acc = temp;
                 l |= 1;
             }
         }
-        l >>= 1;
 
         (l ^ self.offset).min(self.net_len)
     }
@@ -154,40 +158,41 @@ where
     where
         F: FnMut(T::Set) -> bool,
     {
+        if r == 0 {
+            return 0;
+        }
+
         r += self.offset;
-        r >>= r.trailing_zeros();
 
         let mut acc = T::id();
         let mut temp;
 
         // go up
-        while {
-            temp = T::op(self.data[r - 1], acc);
-            pred(temp)
-        } {
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 {
+                r >>= 1
+            }
+
+            temp = T::op(self.data[r], acc);
+            if !pred(temp) {
+                break;
+            }
             if r.is_power_of_two() {
                 return 0;
             }
-
             acc = temp;
-            r -= 1;
-            r >>= r.trailing_zeros();
         }
 
         // go down
-        r -= 1;
-        while {
-            r <<= 1;
-            r < self.data.len()
-        } {
-            temp = T::op(self.data[r | 1], acc);
+        while r < self.offset {
+            r = (r << 1) | 1;
+            temp = T::op(self.data[r], acc);
             if pred(temp) {
                 acc = temp;
-            } else {
-                r |= 1
+                r ^= 1;
             }
         }
-        r >>= 1;
 
         (r ^ self.offset) + 1
     }
@@ -254,3 +259,89 @@ where
         &self.as_slice()[index]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mint::Mint;
+    use ops::{
+        ops::{Additive, Affine},
+        Dual, Identity, SemiGroup,
+    };
+    use rand::Rng;
+
+    use super::*;
+
+    type M = Mint<998244353>;
+
+    fn random_affine(rng: &mut impl Rng) -> (M, M) {
+        (M::new(rng.random()), M::new(rng.random()))
+    }
+
+    #[test]
+    fn non_commutative() {
+        let mut rng = rand::rng();
+        for n in (0..40).chain([100, 128]) {
+            let mut naive = Vec::from_iter((0..n).map(|_| random_affine(&mut rng)));
+            let mut segtree = Segtree::<Affine<M>>::from(naive.clone());
+            let mut dual = Segtree::<Dual<Affine<M>>>::from(naive.clone());
+
+            for _ in 0..1000 {
+                if n > 0 && rng.random_bool(0.2) {
+                    let i = rng.random_range(0..n);
+                    let f = random_affine(&mut rng);
+                    naive[i] = f;
+                    segtree.point_update_with(i, |_| f);
+                    dual.point_update_with(i, |_| f);
+                }
+
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                assert_eq!(
+                    segtree.range_query(l..r),
+                    naive[l..r]
+                        .iter()
+                        .fold(Affine::id(), |acc, f| Affine::op(acc, *f))
+                );
+                assert_eq!(
+                    dual.range_query(l..r),
+                    naive[l..r]
+                        .iter()
+                        .rev()
+                        .fold(Affine::id(), |acc, f| Affine::op(acc, *f))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn partition() {
+        let mut rng = rand::rng();
+        for n in (0..40).chain([100, 128]) {
+            let value = Vec::from_iter((0..n).map(|_| rng.random_range(0..10)));
+            let segtree = Segtree::<Additive<u32>>::from(value.clone());
+
+            for _ in 0..1000 {
+                let i = rng.random_range(0..=n);
+                let k = rng.random_range(0..50);
+
+                let mut sum = 0;
+                let r = (i..n)
+                    .find(|&j| {
+                        sum += value[j];
+                        sum > k
+                    })
+                    .unwrap_or(n);
+                assert_eq!(segtree.partition_right(i, |v| v <= k), r);
+
+                let mut sum = 0;
+                let l = (0..i)
+                    .rfind(|&j| {
+                        sum += value[j];
+                        sum > k
+                    })
+                    .map_or(0, |j| j + 1);
+                assert_eq!(segtree.partition_left(i, |v| v <= k), l);
+            }
+        }
+    }
+}
@@ -0,0 +1,501 @@
+use std::ops::{Bound, Range, RangeBounds};
+
+use num_traits::{PrimInt, Signed};
+use ops::{
+    ops::{Additive, Max, Min},
+    Identity, Monoid, SemiGroup,
+};
+
+#[derive(Debug, Clone, Copy)]
+struct Node<T> {
+    /// 最大値と 2 番目の最大値。存在しなければ`Max::id()`とする。
+    max: [T; 2],
+    max_count: usize,
+    /// 最小値と 2 番目の最小値。存在しなければ`Min::id()`とする。
+    min: [T; 2],
+    min_count: usize,
+    sum: T,
+    /// 子ノードに保留された加算
+    add: T,
+    len: usize,
+}
+
+impl<T> Node<T>
+where
+    T: PrimInt + Signed,
+    Min<T>: Monoid<Set = T>,
+    Max<T>: Monoid<Set = T>,
+{
+    fn leaf(value: T) -> Self {
+        Self {
+            max: [value, Max::id()],
+            max_count: 1,
+            min: [value, Min::id()],
+            min_count: 1,
+            sum: value,
+            add: T::zero(),
+            len: 1,
+        }
+    }
+
+    fn merge(lhs: &Self, rhs: &Self) -> Self {
+        // 上位 2 つの値と、最大値の個数
+        let top2 = |x: [T; 2], cx: usize, y: [T; 2], cy: usize, op: fn(T, T) -> T| {
+            if x[0] == y[0] {
+                ([x[0], op(x[1], y[1])], cx + cy)
+            } else if op(x[0], y[0]) == x[0] {
+                ([x[0], op(x[1], y[0])], cx)
+            } else {
+                ([y[0], op(x[0], y[1])], cy)
+            }
+        };
+        let (max, max_count) = top2(lhs.max, lhs.max_count, rhs.max, rhs.max_count, Max::op);
+        let (min, min_count) = top2(lhs.min, lhs.min_count, rhs.min, rhs.min_count, Min::op);
+
+        Self {
+            max,
+            max_count,
+            min,
+            min_count,
+            sum: Additive::op(lhs.sum, rhs.sum),
+            add: T::zero(),
+            len: lhs.len + rhs.len,
+        }
+    }
+
+    #[inline]
+    fn count(n: usize) -> T {
+        T::from(n).unwrap()
+    }
+
+    /// 全要素に`x`を加える。
+    fn add(&mut self, x: T) {
+        for v in [&mut self.max[0], &mut self.min[0], &mut self.add] {
+            *v = *v + x
+        }
+        if self.max[1] != Max::id() {
+            self.max[1] = self.max[1] + x
+        }
+        if self.min[1] != Min::id() {
+            self.min[1] = self.min[1] + x
+        }
+        self.sum = self.sum + x * Self::count(self.len);
+    }
+
+    /// 最大値を`x`に減らす。`max[1] < x < max[0]`を満たす必要がある。
+    fn chmin(&mut self, x: T) {
+        self.sum = self.sum - (self.max[0] - x) * Self::count(self.max_count);
+        // 要素の種類が 1 つ または 2 つのとき、最小値側も変化する
+        if self.min[0] == self.max[0] {
+            self.min[0] = x
+        } else if self.min[1] == self.max[0] {
+            self.min[1] = x
+        }
+        self.max[0] = x;
+    }
+
+    /// 最小値を`x`に増やす。`min[0] < x < min[1]`を満たす必要がある。
+    fn chmax(&mut self, x: T) {
+        self.sum = self.sum + (x - self.min[0]) * Self::count(self.min_count);
+        if self.max[0] == self.min[0] {
+            self.max[0] = x
+        } else if self.max[1] == self.min[0] {
+            self.max[1] = x
+        }
+        self.min[0] = x;
+    }
+}
+
+/// 区間 chmin・区間 chmax・区間加算・区間代入と、区間和・区間最小値・区間最大値を処理するデータ構造。
+///
+/// 更新はならし *O*(log^2 *N*) 時間で処理する。
+/// 要素は`T::MIN`と`T::MAX`を除く値でなければならない。
+#[derive(Debug, Clone)]
+pub struct SegtreeBeats<T> {
+    /// 1-origin の二分木。ノード`k`の子は`2k`と`2k + 1`である。
+    nodes: Box<[Node<T>]>,
+    net_len: usize,
+}
+
+impl<T> SegtreeBeats<T>
+where
+    T: PrimInt + Signed,
+    Min<T>: Monoid<Set = T>,
+    Max<T>: Monoid<Set = T>,
+{
+    fn parse_range<R>(&self, range: R) -> Range<usize>
+    where
+        R: RangeBounds<usize>,
+    {
+        let l = match range.start_bound() {
+            Bound::Included(&l) => l,
+            Bound::Excluded(&l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(&r) => r + 1,
+            Bound::Excluded(&r) => r,
+            Bound::Unbounded => self.net_len,
+        };
+        assert!(r <= self.net_len, "index out of bounds");
+
+        l..r
+    }
+
+    fn build(&mut self, k: usize, values: &[T]) {
+        if let [v] = values {
+            self.nodes[k] = Node::leaf(*v);
+            return;
+        }
+
+        let (left, right) = values.split_at(values.len() / 2);
+        self.build(k << 1, left);
+        self.build((k << 1) | 1, right);
+        self.pull(k);
+    }
+
+    #[inline]
+    fn pull(&mut self, k: usize) {
+        self.nodes[k] = Node::merge(&self.nodes[k << 1], &self.nodes[(k << 1) | 1]);
+    }
+
+    /// 保留中の加算と、親の最大値・最小値を子ノードに伝搬する。
+    fn propagate(&mut self, k: usize) {
+        let Node { max, min, add, .. } = self.nodes[k];
+        for c in [k << 1, (k << 1) | 1] {
+            let child = &mut self.nodes[c];
+            if add != T::zero() {
+                child.add(add);
+            }
+            if max[0] < child.max[0] {
+                child.chmin(max[0]);
+            }
+            if min[0] > child.min[0] {
+                child.chmax(min[0]);
+            }
+        }
+        self.nodes[k].add = T::zero();
+    }
+
+    fn chmin_inner(&mut self, k: usize, [lo, hi]: [usize; 2], [l, r]: [usize; 2], x: T) {
+        if r <= lo || hi <= l || self.nodes[k].max[0] <= x {
+            return;
+        }
+        if l <= lo && hi <= r && self.nodes[k].max[1] < x {
+            self.nodes[k].chmin(x);
+            return;
+        }
+
+        self.propagate(k);
+        let mid = lo + (hi - lo) / 2;
+        self.chmin_inner(k << 1, [lo, mid], [l, r], x);
+        self.chmin_inner((k << 1) | 1, [mid, hi], [l, r], x);
+        self.pull(k);
+    }
+
+    fn chmax_inner(&mut self, k: usize, [lo, hi]: [usize; 2], [l, r]: [usize; 2], x: T) {
+        if r <= lo || hi <= l || self.nodes[k].min[0] >= x {
+            return;
+        }
+        if l <= lo && hi <= r && self.nodes[k].min[1] > x {
+            self.nodes[k].chmax(x);
+            return;
+        }
+
+        self.propagate(k);
+        let mid = lo + (hi - lo) / 2;
+        self.chmax_inner(k << 1, [lo, mid], [l, r], x);
+        self.chmax_inner((k << 1) | 1, [mid, hi], [l, r], x);
+        self.pull(k);
+    }
+
+    fn add_inner(&mut self, k: usize, [lo, hi]: [usize; 2], [l, r]: [usize; 2], x: T) {
+        if r <= lo || hi <= l {
+            return;
+        }
+        if l <= lo && hi <= r {
+            self.nodes[k].add(x);
+            return;
+        }
+
+        self.propagate(k);
+        let mid = lo + (hi - lo) / 2;
+        self.add_inner(k << 1, [lo, mid], [l, r], x);
+        self.add_inner((k << 1) | 1, [mid, hi], [l, r], x);
+        self.pull(k);
+    }
+
+    fn fold(&mut self, k: usize, [lo, hi]: [usize; 2], [l, r]: [usize; 2]) -> Option<Node<T>> {
+        if r <= lo || hi <= l {
+            return None;
+        }
+        if l <= lo && hi <= r {
+            return Some(self.nodes[k]);
+        }
+
+        self.propagate(k);
+        let mid = lo + (hi - lo) / 2;
+        match (
+            self.fold(k << 1, [lo, mid], [l, r]),
+            self.fold((k << 1) | 1, [mid, hi], [l, r]),
+        ) {
+            (Some(x), Some(y)) => Some(Node::merge(&x, &y)),
+            (x, y) => x.or(y),
+        }
+    }
+
+    /// 区間内の各要素`a_i`を`min(a_i, x)`で更新する。
+    ///
+    /// # Time Complexity
+    ///
+    /// ならし *O*(log^2 *N*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_chmin<R>(&mut self, range: R, x: T)
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = self.parse_range(range);
+        if start < end {
+            self.chmin_inner(1, [0, self.net_len], [start, end], x);
+        }
+    }
+
+    /// 区間内の各要素`a_i`を`max(a_i, x)`で更新する。
+    ///
+    /// # Time Complexity
+    ///
+    /// ならし *O*(log^2 *N*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_chmax<R>(&mut self, range: R, x: T)
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = self.parse_range(range);
+        if start < end {
+            self.chmax_inner(1, [0, self.net_len], [start, end], x);
+        }
+    }
+
+    /// 区間内の各要素に`x`を加える。
+    ///
+    /// # Time Complexity
+    ///
+    /// ならし *O*(log^2 *N*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_add<R>(&mut self, range: R, x: T)
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = self.parse_range(range);
+        if start < end {
+            self.add_inner(1, [0, self.net_len], [start, end], x);
+        }
+    }
+
+    /// 区間内の各要素を`x`で置き換える。
+    ///
+    /// # Time Complexity
+    ///
+    /// ならし *O*(log^2 *N*)
+    ///
+    /// # Panics
+    ///
+    /// `..`は問題ないが、明示的に範囲外の端点を指定するとパニックする。
+    pub fn range_assign<R>(&mut self, range: R, x: T)
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = self.parse_range(range);
+        self.range_chmin(range.clone(), x);
+        self.range_chmax(range, x);
+    }
+
+    fn range_fold<R>(&mut self, range: R) -> Option<Node<T>>
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = self.parse_range(range);
+        if start >= end {
+            return None;
+        }
+
+        self.fold(1, [0, self.net_len], [start, end])
+    }
+
+    /// 区間和を返す。区間が空なら 0 を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    pub fn range_sum<R>(&mut self, range: R) -> T
+    where
+        R: RangeBounds<usize>,
+    {
+        self.range_fold(range).map_or(T::zero(), |node| node.sum)
+    }
+
+    /// 区間の最小値を返す。区間が空なら`Min::id()`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    pub fn range_min<R>(&mut self, range: R) -> T
+    where
+        R: RangeBounds<usize>,
+    {
+        self.range_fold(range).map_or(Min::id(), |node| node.min[0])
+    }
+
+    /// 区間の最大値を返す。区間が空なら`Max::id()`を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    pub fn range_max<R>(&mut self, range: R) -> T
+    where
+        R: RangeBounds<usize>,
+    {
+        self.range_fold(range).map_or(Max::id(), |node| node.max[0])
+    }
+
+    /// `i`番目の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_get(&mut self, i: usize) -> T {
+        assert!(i < self.net_len, "index out of bounds");
+
+        self.range_sum(i..=i)
+    }
+
+    /// # Time Complexity
+    ///
+    /// *O*(*N* log *N*)
+    pub fn to_vec(&mut self) -> Vec<T> {
+        (0..self.net_len).map(|i| self.point_get(i)).collect()
+    }
+}
+
+impl<T> From<Vec<T>> for SegtreeBeats<T>
+where
+    T: PrimInt + Signed,
+    Min<T>: Monoid<Set = T>,
+    Max<T>: Monoid<Set = T>,
+{
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    fn from(values: Vec<T>) -> Self {
+        let mut segtree = Self {
+            nodes: vec![Node::leaf(T::zero()); (values.len().next_power_of_two() << 1).max(2)]
+                .into_boxed_slice(),
+            net_len: values.len(),
+        };
+        if !values.is_empty() {
+            segtree.build(1, &values);
+        }
+
+        segtree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::rng();
+        for n in (0..20).chain([100, 1000]) {
+            let mut naive = Vec::from_iter((0..n).map(|_| rng.random_range(-1000..1000i64)));
+            let mut segtree = SegtreeBeats::from(naive.clone());
+
+            for _ in 0..1000 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                let x = rng.random_range(-1000..1000);
+                match rng.random_range(0..5) {
+                    0 => {
+                        naive[l..r].iter_mut().for_each(|v| *v = (*v).min(x));
+                        segtree.range_chmin(l..r, x);
+                    }
+                    1 => {
+                        naive[l..r].iter_mut().for_each(|v| *v = (*v).max(x));
+                        segtree.range_chmax(l..r, x);
+                    }
+                    2 => {
+                        naive[l..r].iter_mut().for_each(|v| *v += x);
+                        segtree.range_add(l..r, x);
+                    }
+                    3 => {
+                        naive[l..r].fill(x);
+                        segtree.range_assign(l..r, x);
+                    }
+                    _ => {
+                        assert_eq!(segtree.range_sum(l..r), naive[l..r].iter().sum::<i64>());
+                        assert_eq!(
+                            segtree.range_min(l..r),
+                            naive[l..r].iter().copied().min().unwrap_or(i64::MAX)
+                        );
+                        assert_eq!(
+                            segtree.range_max(l..r),
+                            naive[l..r].iter().copied().max().unwrap_or(i64::MIN)
+                        );
+                    }
+                }
+            }
+
+            assert_eq!(segtree.to_vec(), naive);
+        }
+    }
+
+    #[test]
+    fn few_distinct_values() {
+        // 値の種類が少ないと、最大値と最小値が一致するノードが多くなる
+        let mut rng = rand::rng();
+        for n in 1..50 {
+            let mut naive = Vec::from_iter((0..n).map(|_| rng.random_range(0..3i32)));
+            let mut segtree = SegtreeBeats::from(naive.clone());
+
+            for _ in 0..500 {
+                let l = rng.random_range(0..n);
+                let r = rng.random_range(l + 1..=n);
+                let x = rng.random_range(-1..4);
+                match rng.random_range(0..3) {
+                    0 => {
+                        naive[l..r].iter_mut().for_each(|v| *v = (*v).min(x));
+                        segtree.range_chmin(l..r, x);
+                    }
+                    1 => {
+                        naive[l..r].iter_mut().for_each(|v| *v = (*v).max(x));
+                        segtree.range_chmax(l..r, x);
+                    }
+                    _ => {
+                        naive[l..r].iter_mut().for_each(|v| *v += x.signum());
+                        segtree.range_add(l..r, x.signum());
+                    }
+                }
+
+                let i = rng.random_range(0..n);
+                assert_eq!(segtree.point_get(i), naive[i]);
+                assert_eq!(segtree.range_sum(..), naive.iter().sum::<i32>());
+            }
+        }
+    }
+}
@@ -1,8 +1,14 @@
-use ops::{marker::Idempotent, SemiGroup};
+use ops::{
+    marker::{Commutative, Idempotent},
+    SemiGroup,
+};
+use rectangle::NDArray;
 
-use std::{ops::RangeBounds, usize};
+use std::ops::RangeBounds;
 
 /// 冪等性を満たす半群について区間クエリを定数時間で返すデータ構造
+///
+/// 非可換な演算でも、左から順に畳み込んだ結果を返す。
 #[derive(Clone, Debug)]
 pub struct SparseTable<T: SemiGroup + Idempotent> {
     table: Box<[T::Set]>,
@@ -57,7 +63,7 @@ where
         if value.is_empty() {
             return Self {
                 table: Vec::new().into_boxed_slice(),
-                partition: Vec::new().into_boxed_slice(),
+                partition: vec![0; 2].into_boxed_slice(),
             };
         }
 
@@ -110,3 +116,240 @@ where
         }
     }
 }
+
+/// 冪等性を満たす可換な半群について、長方形領域のクエリを定数時間で返すデータ構造
+///
+/// # Example
+///
+/// ```
+/// use ops::ops::Max;
+/// use rectangle::NDArray;
+/// use sparse_table::SparseTable2D;
+///
+/// let grid = NDArray::from_fn([3, 4], |[i, j]| (i * 7 + j * 5) % 11);
+/// let sparse_table = SparseTable2D::<Max<usize>>::from(grid);
+///
+/// assert_eq!(sparse_table.range_query(.., ..), Some(10));
+/// assert_eq!(sparse_table.range_query(1..3, ..2), Some(8));
+/// assert_eq!(sparse_table.range_query(1..1, ..), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SparseTable2D<T: SemiGroup + Commutative + Idempotent> {
+    /// `tables[a * width + b]`の`[i, j]`成分は、`[i, i + 2^a) × [j, j + 2^b)`の畳み込み
+    tables: Box<[NDArray<T::Set, 2>]>,
+    /// 列方向の段数
+    width: usize,
+    shape: [usize; 2],
+}
+
+impl<T> SparseTable2D<T>
+where
+    T: SemiGroup + Commutative + Idempotent,
+    T::Set: Copy,
+{
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    ///
+    /// # Panics
+    ///
+    /// - `rows` should be within `0..N`
+    /// - `cols` should be within `0..M`
+    pub fn range_query<R, C>(&self, rows: R, cols: C) -> Option<T::Set>
+    where
+        R: RangeBounds<usize>,
+        C: RangeBounds<usize>,
+    {
+        let [(il, ir), (jl, jr)] = [
+            parse_range(rows, self.shape[0]),
+            parse_range(cols, self.shape[1]),
+        ];
+        if il >= ir || jl >= jr {
+            return None;
+        }
+
+        let (a, b) = ((ir - il).ilog2() as usize, (jr - jl).ilog2() as usize);
+        let table = &self.tables[a * self.width + b];
+        let (i, j) = (ir - (1 << a), jr - (1 << b));
+        Some(T::op(
+            T::op(table[[il, jl]], table[[il, j]]),
+            T::op(table[[i, jl]], table[[i, j]]),
+        ))
+    }
+}
+
+impl<T> From<NDArray<T::Set, 2>> for SparseTable2D<T>
+where
+    T: SemiGroup + Commutative + Idempotent,
+    T::Set: Copy,
+{
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NM* log *N* log *M*)
+    fn from(value: NDArray<T::Set, 2>) -> Self {
+        let shape @ [n, m] = value.shape();
+        // panic 回避のため
+        if n == 0 || m == 0 {
+            return Self {
+                tables: Box::new([]),
+                width: 0,
+                shape,
+            };
+        }
+
+        let (height, width) = (n.ilog2() as usize + 1, m.ilog2() as usize + 1);
+        let mut tables: Vec<NDArray<T::Set, 2>> = Vec::with_capacity(height * width);
+        tables.push(value);
+        for a in 0..height {
+            if a > 0 {
+                // 行方向に倍にする
+                let prev = &tables[(a - 1) * width];
+                let half = 1 << (a - 1);
+                let [h, w] = prev.shape();
+                let next = NDArray::from_fn([h - half, w], |[i, j]| {
+                    T::op(prev[[i, j]], prev[[i + half, j]])
+                });
+                tables.push(next);
+            }
+            for b in 1..width {
+                // 列方向に倍にする
+                let prev = &tables[a * width + b - 1];
+                let half = 1 << (b - 1);
+                let [h, w] = prev.shape();
+                let next = NDArray::from_fn([h, w - half], |[i, j]| {
+                    T::op(prev[[i, j]], prev[[i, j + half]])
+                });
+                tables.push(next);
+            }
+        }
+
+        Self {
+            tables: tables.into_boxed_slice(),
+            width,
+            shape,
+        }
+    }
+}
+
+fn parse_range<R>(range: R, len: usize) -> (usize, usize)
+where
+    R: RangeBounds<usize>,
+{
+    let l = match range.start_bound() {
+        std::ops::Bound::Included(l) => *l,
+        std::ops::Bound::Excluded(l) => l + 1,
+        std::ops::Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        std::ops::Bound::Included(r) => r + 1,
+        std::ops::Bound::Excluded(r) => *r,
+        std::ops::Bound::Unbounded => len,
+    };
+    assert!(r <= len, "index out of bounds");
+
+    (l, r)
+}
+
+#[cfg(test)]
+mod tests {
+    use ops::{
+        ops::{Max, Min},
+        Dual,
+    };
+    use rand::Rng;
+
+    use super::*;
+
+    /// 左端の値を返す非可換な冪等半群
+    struct First;
+
+    impl SemiGroup for First {
+        type Set = usize;
+
+        fn op(lhs: Self::Set, _: Self::Set) -> Self::Set {
+            lhs
+        }
+    }
+
+    impl Idempotent for First {}
+
+    #[test]
+    fn non_commutative() {
+        for n in 0..100 {
+            let first = SparseTable::<First>::from_iter(0..n);
+            let last = SparseTable::<Dual<First>>::from_iter(0..n);
+
+            assert_eq!(first.range_query(..), (n > 0).then_some(0));
+            assert_eq!(last.range_query(..), n.checked_sub(1));
+            for l in 0..n {
+                for r in l + 1..=n {
+                    assert_eq!(first.range_query(l..r), Some(l));
+                    assert_eq!(last.range_query(l..r), Some(r - 1));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = rand::rng();
+        for n in 0..100 {
+            let value = Vec::from_iter((0..n).map(|_| rng.random_range(0..1000)));
+            let sparse_table = SparseTable::<Min<u32>>::from(value.clone());
+
+            for l in 0..n {
+                for r in l..=n {
+                    assert_eq!(
+                        sparse_table.range_query(l..r),
+                        value[l..r].iter().min().copied()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds_2d() {
+        let grid = NDArray::from_fn([3, 4], |[i, j]| i * 4 + j);
+        let sparse_table = SparseTable2D::<Max<usize>>::from(grid);
+        sparse_table.range_query(0..1, 0..8);
+    }
+
+    #[test]
+    fn random_2d() {
+        let mut rng = rand::rng();
+        for (n, m) in [
+            (0, 0),
+            (0, 5),
+            (5, 0),
+            (1, 1),
+            (1, 17),
+            (13, 1),
+            (16, 16),
+            (20, 9),
+        ] {
+            let grid = NDArray::from_fn([n, m], |_| rng.random_range(0..1000));
+            let min = SparseTable2D::<Min<u32>>::from(grid.clone());
+            let max = SparseTable2D::<Max<u32>>::from(grid.clone());
+
+            for il in 0..=n {
+                for ir in il..=n {
+                    for jl in 0..=m {
+                        for jr in jl..=m {
+                            let naive = (il..ir).flat_map(|i| (jl..jr).map(move |j| [i, j]));
+                            assert_eq!(
+                                min.range_query(il..ir, jl..jr),
+                                naive.clone().map(|ij| grid[ij]).min()
+                            );
+                            assert_eq!(
+                                max.range_query(il..ir, jl..jr),
+                                naive.map(|ij| grid[ij]).max()
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use ops::SemiGroup;
+
+use std::{fmt::Debug, ops::RangeBounds};
+
+/// 半群の値の列に対して、区間クエリを *O*(1) 時間、一点更新を *O*(√*N*) 時間で処理するデータ構造。
+/// 空間計算量は *O*(*N* log log *N*) である。
+///
+/// 非可換な演算でも、左から順に畳み込んだ結果を返す。
+#[derive(Debug, Clone)]
+pub struct SqrtTree<T>
+where
+    T: SemiGroup,
+    T::Set: Copy,
+{
+    /// 先頭`len`個は与えられたデータ列、残りは第 0 層の各ブロックの畳み込み（インデックス部）。
+    /// インデックス部も第 1 層以降の構造で管理する。
+    values: Box<[T::Set]>,
+    len: usize,
+    /// ⌈lg *N*⌉
+    lg: u32,
+    /// 各層が担当する区間の大きさの対数。`layers[k + 1] = ⌈layers[k] / 2⌉`
+    layers: Box<[u32]>,
+    /// `on_layer[k]`は大きさ`2^k`の区間を担当する最も深い層
+    on_layer: Box<[usize]>,
+    /// `prefix[k][i]`は第`k`層において、`i`を含むブロックの先頭から`i`までの畳み込み
+    prefix: Box<[Box<[T::Set]>]>,
+    /// `suffix[k][i]`は第`k`層において、`i`から`i`を含むブロックの末尾までの畳み込み
+    suffix: Box<[Box<[T::Set]>]>,
+    /// `between[k - 1]`は第`k`層において、連続するブロックの畳み込み
+    between: Box<[Box<[T::Set]>]>,
+}
+
+impl<T> SqrtTree<T>
+where
+    T: SemiGroup,
+    T::Set: Copy,
+{
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// インデックス部の`between`の添え字をずらす量
+    #[inline]
+    fn index_offset(&self) -> usize {
+        (1 << self.lg) - self.len
+    }
+
+    /// `l..r`番目の要素について、第`layer`層の`prefix`と`suffix`を計算する。
+    fn build_block(&mut self, layer: usize, l: usize, r: usize) {
+        let (prefix, suffix) = (&mut self.prefix[layer], &mut self.suffix[layer]);
+
+        prefix[l] = self.values[l];
+        for i in l + 1..r {
+            prefix[i] = T::op(prefix[i - 1], self.values[i]);
+        }
+        suffix[r - 1] = self.values[r - 1];
+        for i in (l..r - 1).rev() {
+            suffix[i] = T::op(self.values[i], suffix[i + 1]);
+        }
+    }
+
+    /// 第`layer`層の区間`l_bound..r_bound`について、`between`を計算する。
+    fn build_between(&mut self, layer: usize, l_bound: usize, r_bound: usize, offset: usize) {
+        let block_log = self.layers[layer].div_ceil(2);
+        let count_log = self.layers[layer] / 2;
+        let count = (r_bound - l_bound).div_ceil(1 << block_log);
+
+        let (suffix, between) = (&self.suffix[layer], &mut self.between[layer - 1]);
+        for i in 0..count {
+            let base = offset + l_bound + (i << count_log);
+            let mut acc = suffix[l_bound + (i << block_log)];
+            between[base + i] = acc;
+            for j in i + 1..count {
+                acc = T::op(acc, suffix[l_bound + (j << block_log)]);
+                between[base + j] = acc;
+            }
+        }
+    }
+
+    fn build(&mut self, layer: usize, l_bound: usize, r_bound: usize, offset: usize) {
+        if layer >= self.layers.len() {
+            return;
+        }
+
+        let block_size = 1 << self.layers[layer].div_ceil(2);
+        for l in (l_bound..r_bound).step_by(block_size) {
+            let r = (l + block_size).min(r_bound);
+            self.build_block(layer, l, r);
+            self.build(layer + 1, l, r, offset);
+        }
+
+        if layer == 0 {
+            // 第 0 層のブロックは大きいので、インデックス部を再帰的に管理する
+            for (i, l) in (0..self.len).step_by(block_size).enumerate() {
+                self.values[self.len + i] = self.suffix[0][l];
+            }
+            let index_len = self.values.len() - self.len;
+            self.build(1, self.len, self.len + index_len, self.index_offset());
+        } else {
+            self.build_between(layer, l_bound, r_bound, offset);
+        }
+    }
+
+    fn update(&mut self, layer: usize, l_bound: usize, r_bound: usize, offset: usize, i: usize) {
+        if layer >= self.layers.len() {
+            return;
+        }
+
+        let block_log = self.layers[layer].div_ceil(2);
+        let block = (i - l_bound) >> block_log;
+        let l = l_bound + (block << block_log);
+        let r = (l + (1 << block_log)).min(r_bound);
+        self.build_block(layer, l, r);
+
+        if layer == 0 {
+            self.values[self.len + block] = self.suffix[0][l];
+            let index_len = self.values.len() - self.len;
+            self.update(
+                1,
+                self.len,
+                self.len + index_len,
+                self.index_offset(),
+                self.len + block,
+            );
+        } else {
+            self.build_between(layer, l_bound, r_bound, offset);
+        }
+
+        self.update(layer + 1, l, r, offset, i);
+    }
+
+    /// `l..=r`番目の要素を畳み込む。`base`は区間の添え字の基準点である。
+    fn query(&self, l: usize, r: usize, offset: usize, base: usize) -> T::Set {
+        if l == r {
+            return self.values[l];
+        }
+        if l + 1 == r {
+            return T::op(self.values[l], self.values[r]);
+        }
+
+        // `l`と`r`を同じ区間に含み、異なるブロックに分ける層を選ぶ
+        let layer = self.on_layer[((l - base) ^ (r - base)).ilog2() as usize + 1];
+        let block_log = self.layers[layer].div_ceil(2);
+        let count_log = self.layers[layer] / 2;
+        let l_bound = (((l - base) >> self.layers[layer]) << self.layers[layer]) + base;
+        let l_block = ((l - l_bound) >> block_log) + 1;
+        let r_block = ((r - l_bound) >> block_log) - 1;
+
+        let mut res = self.suffix[layer][l];
+        if l_block <= r_block {
+            let mid = if layer == 0 {
+                self.query(
+                    self.len + l_block,
+                    self.len + r_block,
+                    self.index_offset(),
+                    self.len,
+                )
+            } else {
+                self.between[layer - 1][offset + l_bound + (l_block << count_log) + r_block]
+            };
+            res = T::op(res, mid)
+        }
+
+        T::op(res, self.prefix[layer][r])
+    }
+
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn range_query<R>(&self, range: R) -> Option<T::Set>
+    where
+        R: RangeBounds<usize>,
+    {
+        let l = match range.start_bound() {
+            std::ops::Bound::Included(l) => *l,
+            std::ops::Bound::Excluded(l) => l + 1,
+            std::ops::Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            std::ops::Bound::Included(r) => r + 1,
+            std::ops::Bound::Excluded(r) => *r,
+            std::ops::Bound::Unbounded => self.len,
+        };
+        assert!(r <= self.len, "index out of bounds");
+
+        if l >= r {
+            None
+        } else {
+            Some(self.query(l, r - 1, 0, 0))
+        }
+    }
+
+    /// `i`番目の要素を返す。
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_get(&self, i: usize) -> T::Set {
+        assert!(i < self.len, "index out of bounds");
+
+        self.values[i]
+    }
+
+    /// `i`番目の要素を`value`で置き換える。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(√*N*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_set(&mut self, i: usize, value: T::Set) {
+        assert!(i < self.len, "index out of bounds");
+
+        self.values[i] = value;
+        self.update(0, 0, self.len, 0, i);
+    }
+}
+
+impl<T> From<Vec<T::Set>> for SqrtTree<T>
+where
+    T: SemiGroup,
+    T::Set: Copy,
+{
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N* log log *N*)
+    fn from(mut values: Vec<T::Set>) -> Self {
+        let len = values.len();
+        let lg = len.next_power_of_two().trailing_zeros();
+
+        let mut layers = Vec::new();
+        let mut on_layer = vec![0; lg as usize + 1];
+        let mut k = lg;
+        while k > 1 {
+            on_layer[k as usize] = layers.len();
+            layers.push(k);
+            k = k.div_ceil(2)
+        }
+        for k in (0..lg as usize).rev() {
+            on_layer[k] = on_layer[k].max(on_layer[k + 1])
+        }
+
+        // 単位元がないので、未計算の部分は先頭の要素で埋めておく。読み出す前に必ず上書きされる。
+        let Some(&fill) = values.first() else {
+            return Self {
+                values: Box::new([]),
+                len,
+                lg,
+                layers: layers.into_boxed_slice(),
+                on_layer: on_layer.into_boxed_slice(),
+                prefix: Box::new([]),
+                suffix: Box::new([]),
+                between: Box::new([]),
+            };
+        };
+        let block_log = lg.div_ceil(2);
+        values.resize(len + len.div_ceil(1 << block_log), fill);
+
+        let table = vec![fill; values.len()].into_boxed_slice();
+        let mut sqrt_tree = Self {
+            prefix: vec![table.clone(); layers.len()].into_boxed_slice(),
+            suffix: vec![table; layers.len()].into_boxed_slice(),
+            between: vec![
+                vec![fill; (1 << lg) + (1 << block_log)].into_boxed_slice();
+                layers.len().saturating_sub(1)
+            ]
+            .into_boxed_slice(),
+            values: values.into_boxed_slice(),
+            len,
+            lg,
+            layers: layers.into_boxed_slice(),
+            on_layer: on_layer.into_boxed_slice(),
+        };
+        sqrt_tree.build(0, 0, len, 0);
+
+        sqrt_tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mint::Mint;
+    use ops::{
+        ops::{Additive, Affine},
+        Dual, Identity,
+    };
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn sum() {
+        for n in 0..100 {
+            let sqrt_tree = SqrtTree::<Additive<usize>>::from(Vec::from_iter(0..n));
+            assert_eq!(
+                sqrt_tree.range_query(..),
+                n.checked_sub(1).map(|m| n * m / 2)
+            );
+            for l in 0..n {
+                for r in l..n {
+                    assert_eq!(
+                        sqrt_tree.range_query(l..=r),
+                        Some((l + r) * (r - l + 1) / 2),
+                        "{:?}",
+                        l..=r
+                    )
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn non_commutative() {
+        type M = Mint<998244353>;
+
+        let mut rng = rand::rng();
+        for n in (0..70).chain([255, 256, 257, 1000, 5000]) {
+            let mut value =
+                Vec::from_iter((0..n).map(|_| (M::new(rng.random()), M::new(rng.random()))));
+            let mut sqrt_tree = SqrtTree::<Affine<M>>::from(value.clone());
+            let mut dual = SqrtTree::<Dual<Affine<M>>>::from(value.clone());
+
+            for _ in 0..1000 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                if rng.random_bool(0.2) && l < n {
+                    let f = (M::new(rng.random()), M::new(rng.random()));
+                    value[l] = f;
+                    sqrt_tree.point_set(l, f);
+                    dual.point_set(l, f);
+                    assert_eq!(sqrt_tree.point_get(l), f);
+                }
+
+                let naive = value[l..r]
+                    .iter()
+                    .fold(Affine::id(), |acc, f| Affine::op(acc, *f));
+                assert_eq!(sqrt_tree.range_query(l..r), (l < r).then_some(naive));
+                let naive = value[l..r]
+                    .iter()
+                    .rev()
+                    .fold(Affine::id(), |acc, f| Affine::op(acc, *f));
+                assert_eq!(dual.range_query(l..r), (l < r).then_some(naive));
+            }
+        }
+    }
+}
@@ -0,0 +1,353 @@
+//! Sliding Window Aggregation
+//!
+//! 両端の追加・削除と、要素全体の畳み込みをならし *O*(1) 時間で処理する。
+//! 非可換な演算でも、先頭から順に畳み込んだ結果を返す。
+
+use ops::Monoid;
+
+/// 末尾への追加・先頭からの削除と、全体の畳み込みを処理するキュー。
+///
+/// # Example
+///
+/// ```
+/// use ops::ops::Min;
+/// use swag::FoldableQueue;
+///
+/// // スライド最小値
+/// let a = [3, 1, 4, 1, 5, 9, 2, 6];
+/// let mut window = FoldableQueue::<Min<i32>>::new();
+/// let mut min = vec![];
+/// for (i, &x) in a.iter().enumerate() {
+///     window.push_back(x);
+///     if i >= 3 {
+///         min.push(window.fold());
+///         window.pop_front();
+///     }
+/// }
+/// assert_eq!(min, [1, 1, 1, 1, 2]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FoldableQueue<T>
+where
+    T: Monoid,
+{
+    /// 先頭側の要素を逆順に積んだスタック。`(値, その値から前半の末尾までの畳み込み)`
+    front: Vec<(T::Set, T::Set)>,
+    /// 末尾側の要素を順に積んだスタック
+    back: Vec<T::Set>,
+    /// `back`の畳み込み
+    back_fold: T::Set,
+}
+
+impl<T> FoldableQueue<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    pub fn new() -> Self {
+        Self {
+            front: Vec::new(),
+            back: Vec::new(),
+            back_fold: T::id(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.front.is_empty() && self.back.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.front.clear();
+        self.back.clear();
+        self.back_fold = T::id();
+    }
+
+    /// 先頭の要素を返す。
+    pub fn front(&self) -> Option<T::Set> {
+        self.front
+            .last()
+            .map(|&(value, _)| value)
+            .or_else(|| self.back.first().copied())
+    }
+
+    /// 末尾の要素を返す。
+    pub fn back(&self) -> Option<T::Set> {
+        self.back
+            .last()
+            .or_else(|| self.front.first().map(|(value, _)| value))
+            .copied()
+    }
+
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn push_back(&mut self, value: T::Set) {
+        self.back_fold = T::op(self.back_fold, value);
+        self.back.push(value);
+    }
+
+    /// # Time Complexity
+    ///
+    /// ならし *O*(1)
+    pub fn pop_front(&mut self) -> Option<T::Set> {
+        if self.front.is_empty() {
+            // 末尾側の要素をすべて先頭側に移す
+            let mut acc = T::id();
+            self.front.reserve(self.back.len());
+            for value in self.back.drain(..).rev() {
+                acc = T::op(value, acc);
+                self.front.push((value, acc));
+            }
+            self.back_fold = T::id();
+        }
+
+        self.front.pop().map(|(value, _)| value)
+    }
+
+    /// 先頭から順に畳み込んだ結果を返す。空なら単位元を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn fold(&self) -> T::Set {
+        let front = self.front.last().map_or(T::id(), |&(_, acc)| acc);
+
+        T::op(front, self.back_fold)
+    }
+}
+
+impl<T> Default for FoldableQueue<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T::Set> for FoldableQueue<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    fn from_iter<I: IntoIterator<Item = T::Set>>(iter: I) -> Self {
+        let mut queue = Self::new();
+        iter.into_iter().for_each(|value| queue.push_back(value));
+
+        queue
+    }
+}
+
+/// 両端への追加・両端からの削除と、全体の畳み込みを処理する両端キュー。
+///
+/// 片方のスタックが空になったら、もう片方の要素を半分ずつに分けて積み直す。
+#[derive(Debug, Clone)]
+pub struct FoldableDeque<T>
+where
+    T: Monoid,
+{
+    /// 先頭側の要素を逆順に積んだスタック。`(値, その値から前半の末尾までの畳み込み)`
+    front: Vec<(T::Set, T::Set)>,
+    /// 末尾側の要素を順に積んだスタック。`(値, 後半の先頭からその値までの畳み込み)`
+    back: Vec<(T::Set, T::Set)>,
+}
+
+impl<T> FoldableDeque<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    pub fn new() -> Self {
+        Self {
+            front: Vec::new(),
+            back: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.front.is_empty() && self.back.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.front.clear();
+        self.back.clear();
+    }
+
+    /// 先頭の要素を返す。
+    pub fn front(&self) -> Option<T::Set> {
+        self.front
+            .last()
+            .or_else(|| self.back.first())
+            .map(|&(value, _)| value)
+    }
+
+    /// 末尾の要素を返す。
+    pub fn back(&self) -> Option<T::Set> {
+        self.back
+            .last()
+            .or_else(|| self.front.first())
+            .map(|&(value, _)| value)
+    }
+
+    fn front_fold(&self) -> T::Set {
+        self.front.last().map_or(T::id(), |&(_, acc)| acc)
+    }
+
+    fn back_fold(&self) -> T::Set {
+        self.back.last().map_or(T::id(), |&(_, acc)| acc)
+    }
+
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn push_front(&mut self, value: T::Set) {
+        let acc = T::op(value, self.front_fold());
+        self.front.push((value, acc));
+    }
+
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn push_back(&mut self, value: T::Set) {
+        let acc = T::op(self.back_fold(), value);
+        self.back.push((value, acc));
+    }
+
+    /// 先頭から順に並べた要素を、先頭側の`mid`個と末尾側に分けて積み直す。
+    fn rebuild(&mut self, values: Vec<T::Set>, mid: usize) {
+        let (front, back) = values.split_at(mid);
+        for &value in front.iter().rev() {
+            self.push_front(value);
+        }
+        for &value in back {
+            self.push_back(value);
+        }
+    }
+
+    /// # Time Complexity
+    ///
+    /// ならし *O*(1)
+    pub fn pop_front(&mut self) -> Option<T::Set> {
+        if self.front.is_empty() {
+            let values = Vec::from_iter(self.back.drain(..).map(|(value, _)| value));
+            let mid = values.len().div_ceil(2);
+            self.rebuild(values, mid);
+        }
+
+        self.front.pop().map(|(value, _)| value)
+    }
+
+    /// # Time Complexity
+    ///
+    /// ならし *O*(1)
+    pub fn pop_back(&mut self) -> Option<T::Set> {
+        if self.back.is_empty() {
+            let values = Vec::from_iter(self.front.drain(..).rev().map(|(value, _)| value));
+            let mid = values.len() / 2;
+            self.rebuild(values, mid);
+        }
+
+        self.back.pop().map(|(value, _)| value)
+    }
+
+    /// 先頭から順に畳み込んだ結果を返す。空なら単位元を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn fold(&self) -> T::Set {
+        T::op(self.front_fold(), self.back_fold())
+    }
+}
+
+impl<T> Default for FoldableDeque<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T::Set> for FoldableDeque<T>
+where
+    T: Monoid<Set: Copy>,
+{
+    fn from_iter<I: IntoIterator<Item = T::Set>>(iter: I) -> Self {
+        let mut deque = Self::new();
+        iter.into_iter().for_each(|value| deque.push_back(value));
+
+        deque
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use mint::Mint;
+    use ops::{ops::Affine, Identity, SemiGroup};
+    use rand::Rng;
+
+    use super::*;
+
+    type M = Mint<998244353>;
+    type F = Affine<M>;
+
+    fn naive_fold(naive: &VecDeque<(M, M)>) -> (M, M) {
+        naive.iter().fold(F::id(), |acc, &f| F::op(acc, f))
+    }
+
+    #[test]
+    fn queue() {
+        let mut rng = rand::rng();
+        let mut naive = VecDeque::new();
+        let mut queue = FoldableQueue::<F>::new();
+        for _ in 0..10000 {
+            if rng.random_bool(0.55) {
+                let f = (M::new(rng.random()), M::new(rng.random()));
+                naive.push_back(f);
+                queue.push_back(f);
+            } else {
+                assert_eq!(queue.pop_front(), naive.pop_front());
+            }
+
+            assert_eq!(queue.len(), naive.len());
+            assert_eq!(queue.front(), naive.front().copied());
+            assert_eq!(queue.back(), naive.back().copied());
+            assert_eq!(queue.fold(), naive_fold(&naive));
+        }
+    }
+
+    #[test]
+    fn deque() {
+        let mut rng = rand::rng();
+        let mut naive = VecDeque::new();
+        let mut deque = FoldableDeque::<F>::new();
+        for _ in 0..10000 {
+            let f = (M::new(rng.random()), M::new(rng.random()));
+            match rng.random_range(0..4) {
+                0 => {
+                    naive.push_back(f);
+                    deque.push_back(f);
+                }
+                1 => {
+                    naive.push_front(f);
+                    deque.push_front(f);
+                }
+                2 => assert_eq!(deque.pop_back(), naive.pop_back()),
+                _ => assert_eq!(deque.pop_front(), naive.pop_front()),
+            }
+
+            assert_eq!(deque.len(), naive.len());
+            assert_eq!(deque.front(), naive.front().copied());
+            assert_eq!(deque.back(), naive.back().copied());
+            assert_eq!(deque.fold(), naive_fold(&naive));
+        }
+    }
+}
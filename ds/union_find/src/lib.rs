@@ -7,10 +7,16 @@ where
     T: Commutative + SemiGroup<Set: Copy>,
 {
     /// 非負なら親へのポインター、負なら要素数を表す。
-    parent_or_size: Box<[i32]>,
+    parent_or_size: Vec<i32>,
 
     /// ノードのもつ値
-    value: Box<[T::Set]>,
+    value: Vec<T::Set>,
+
+    /// 同じ素集合に属する次のノード。各素集合は循環リストをなす。
+    next: Vec<usize>,
+
+    /// 素集合の個数
+    num_components: usize,
 }
 
 impl<T> UnionFind<T>
@@ -23,9 +29,12 @@ where
     ///
     /// *Θ*(*N*)
     pub fn with_values(value: Box<[T::Set]>) -> Self {
+        let n = value.len();
         Self {
-            parent_or_size: vec![-1; value.len()].into_boxed_slice(),
-            value,
+            parent_or_size: vec![-1; n],
+            value: value.into_vec(),
+            next: Vec::from_iter(0..n),
+            num_components: n,
         }
     }
 
@@ -35,10 +44,40 @@ where
     ///
     /// *Θ*(*N*)
     pub fn new(n: usize) -> UnionFind<()> {
-        UnionFind {
-            parent_or_size: vec![-1; n].into_boxed_slice(),
-            value: vec![(); n].into_boxed_slice(),
-        }
+        UnionFind::with_values(vec![(); n].into_boxed_slice())
+    }
+
+    /// ノード数を返す。
+    pub fn len(&self) -> usize {
+        self.parent_or_size.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent_or_size.is_empty()
+    }
+
+    /// 値`value`をもつノードを追加し、そのインデックスを返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1) amortized
+    pub fn push_node(&mut self, value: T::Set) -> usize {
+        let x = self.len();
+        self.parent_or_size.push(-1);
+        self.value.push(value);
+        self.next.push(x);
+        self.num_components += 1;
+
+        x
+    }
+
+    /// 素集合の個数を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    pub fn num_components(&self) -> usize {
+        self.num_components
     }
 
     /// `x`が所属する素集合の代表元のインデックスを返す。
@@ -71,7 +110,8 @@ where
     ///
     /// *O*(α(*N*)) amortized
     pub fn find_value(&mut self, x: usize) -> T::Set {
-        self.value[self.find(x)]
+        let x = self.find(x);
+        self.value[x]
     }
 
     /// `x`と`y`の所属する素集合を結合する。
@@ -94,6 +134,9 @@ where
         self.parent_or_size[x] += self.parent_or_size[y];
         self.value[x] = T::op(self.value[x], self.value[y]);
         self.parent_or_size[y] = x as i32;
+        // 循環リストを連結する
+        self.next.swap(x, y);
+        self.num_components -= 1;
 
         true
     }
@@ -113,7 +156,8 @@ where
     ///
     /// *O*(α(*N*)) amortized
     pub fn size(&mut self, x: usize) -> usize {
-        -self.parent_or_size[self.find(x)] as usize
+        let x = self.find(x);
+        -self.parent_or_size[x] as usize
     }
 
     pub fn leaders(&self) -> impl Iterator<Item = (&i32, &T::Set)> {
@@ -122,4 +166,87 @@ where
             .zip(self.value.iter())
             .filter(|(i, _)| i.is_negative())
     }
+
+    /// `x`が所属する素集合の要素を、`x`から順に返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// 要素数に比例する。
+    pub fn component_of(&self, x: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut current = Some(x);
+        std::iter::from_fn(move || {
+            let res = current?;
+            current = Some(self.next[res]).filter(|&y| y != x);
+            Some(res)
+        })
+    }
+
+    /// 素集合ごとに要素を列挙する。各素集合は代表元から始まる。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*)
+    pub fn groups(&self) -> Vec<Vec<usize>> {
+        let mut groups = Vec::with_capacity(self.num_components);
+        for (x, &p) in self.parent_or_size.iter().enumerate() {
+            if p.is_negative() {
+                let mut group = Vec::with_capacity(-p as usize);
+                group.extend(self.component_of(x));
+                groups.push(group);
+            }
+        }
+
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn groups() {
+        let mut rng = rand::rng();
+        for n in 0..50 {
+            let mut uf = UnionFind::<()>::new(n);
+            // ラベルが等しいノードが同じ素集合に属する
+            let mut label = Vec::from_iter(0..n);
+
+            for _ in 0..100 {
+                if rng.random_bool(0.1) {
+                    label.push(uf.push_node(()));
+                }
+
+                let len = uf.len();
+                if len > 0 {
+                    let (x, y) = (rng.random_range(0..len), rng.random_range(0..len));
+                    let (lx, ly) = (label[x], label[y]);
+                    assert_eq!(uf.union(x, y), lx != ly);
+                    label.iter_mut().filter(|l| **l == ly).for_each(|l| *l = lx);
+                }
+
+                let mut expected = Vec::from_iter((0..len).filter_map(|x| {
+                    let group = Vec::from_iter((0..len).filter(|&y| label[y] == label[x]));
+                    (group[0] == x).then_some(group)
+                }));
+                let mut groups = uf.groups();
+                groups.iter_mut().for_each(|group| group.sort_unstable());
+                groups.sort_unstable();
+                expected.sort_unstable();
+                assert_eq!(groups, expected);
+                assert_eq!(uf.num_components(), expected.len());
+
+                if len > 0 {
+                    let x = rng.random_range(0..len);
+                    let mut component = Vec::from_iter(uf.component_of(x));
+                    assert_eq!(component[0], x);
+                    assert_eq!(component.len(), uf.size(x));
+                    component.sort_unstable();
+                    assert!(expected.contains(&component));
+                }
+            }
+        }
+    }
 }
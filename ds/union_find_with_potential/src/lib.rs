@@ -1,6 +1,9 @@
 use ops::Group;
 
 /// 重み付きの有向辺`a -(w)-> b`で`a = b w`を定義していく。
+///
+/// 群は可換でなくてよい。ポテンシャルは常に右から掛けるので、
+/// 辺`s -(w1)-> u -(w2)-> t`からは`P(s) = P(t) w2 w1`が得られる。
 pub struct UnionFindWithPotential<T>
 where
     T: Group,
@@ -10,7 +13,7 @@ where
 
 impl<T> UnionFindWithPotential<T>
 where
-    T: Group<Set: Clone + Eq> + Copy,
+    T: Group<Set: Clone + Eq>,
 {
     pub fn new(n: usize) -> Self {
         Self {
@@ -75,51 +78,48 @@ where
 
     /// `P(s) = P(t) * potential`の関係を追加する。
     ///
-    /// - 矛盾する場合は`Err()`を返す
+    /// - 矛盾する場合は、既存の関係から定まる`inv(P(t)) P(s)`を`Err`で返す
     /// - 矛盾していないが定義済みの場合は`Ok(false)`を返す
     /// - 新しい関係を追加した場合は`Ok(true)`を返す
     pub fn union(
         &mut self,
         source: usize,
         target: usize,
-        mut potential: T::Set,
-    ) -> Result<bool, ()> {
+        potential: T::Set,
+    ) -> Result<bool, T::Set> {
         if let Some(p) = self.potential(source, target) {
-            if potential == p {
-                return Ok(false);
-            } else {
-                return Err(());
-            }
+            return if potential == p { Ok(false) } else { Err(p) };
         }
 
-        {
-            let mut rs = self.find(source);
-            let mut rt = self.find(target);
+        let rs = self.find(source);
+        let rt = self.find(target);
 
-            // union by size
-            if self.node[rs].parent_or_size > self.node[rt].parent_or_size {
-                std::mem::swap(&mut rs, &mut rt);
-                potential = T::inv(potential)
-            }
+        // P(s) = P(rs) * w_s, P(t) = P(rt) * w_t, P(s) = P(t) * potential
+        // P(rs) = P(s) * inv(w_s) = P(t) * potential * inv(w_s)
+        //                         = P(rt) * w_t * potential * inv(w_s)
+        let w = T::op(
+            T::op(self.node[target].potential.clone(), potential),
+            T::inv(self.node[source].potential.clone()),
+        );
 
-            // P(s) = P(rs) * w_s, P(t) = P(rt) * w_t, P(s) = P(t) * potential
-            // P(rs) = P(s) * inv(w_s) = P(t) * potential * inv(w_s)
-            //                         = P(rt) * w_t * potential * inv(w_s)
-            self.node[rs].parent_or_size += self.node[rt].parent_or_size;
-            self.node[rt] = Node {
-                parent_or_size: rs as i32,
-                potential: T::op(
-                    T::op(self.node[target].potential.clone(), potential),
-                    T::inv(self.node[source].potential.clone()),
-                ),
-            }
-        }
+        // union by size
+        let size = self.node[rs].parent_or_size + self.node[rt].parent_or_size;
+        let (root, child, w) = if self.node[rs].parent_or_size < self.node[rt].parent_or_size {
+            // P(rt) = P(rs) * inv(w)
+            (rs, rt, T::inv(w))
+        } else {
+            (rt, rs, w)
+        };
+        self.node[root].parent_or_size = size;
+        self.node[child] = Node {
+            parent_or_size: root as i32,
+            potential: w,
+        };
 
         Ok(true)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct Node<T>
 where
     T: Group,
@@ -129,3 +129,138 @@ where
     /// `inv(parent) * self`
     potential: T::Set,
 }
+
+impl<T> Clone for Node<T>
+where
+    T: Group<Set: Clone>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            parent_or_size: self.parent_or_size,
+            potential: self.potential.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mint::Mint;
+    use ops::{Identity, Inverse, SemiGroup};
+    use rand::{seq::SliceRandom, Rng};
+
+    use super::*;
+
+    /// 5 次の置換群。`op(f, g)`は`f`で移してから`g`で移す置換
+    struct Permutation;
+
+    impl SemiGroup for Permutation {
+        type Set = Vec<usize>;
+
+        fn op(f: Self::Set, g: Self::Set) -> Self::Set {
+            Vec::from_iter(f.iter().map(|&i| g[i]))
+        }
+    }
+
+    impl Identity for Permutation {
+        fn id() -> Self::Set {
+            Vec::from_iter(0..5)
+        }
+    }
+
+    impl Inverse for Permutation {
+        fn inv(f: Self::Set) -> Self::Set {
+            let mut inv = vec![0; f.len()];
+            for (i, &j) in f.iter().enumerate() {
+                inv[j] = i;
+            }
+            inv
+        }
+    }
+
+    type M = Mint<998244353>;
+
+    /// 法 998244353 での 2 次正則行列のなす群
+    struct Matrix2;
+
+    impl SemiGroup for Matrix2 {
+        type Set = [[M; 2]; 2];
+
+        fn op(a: Self::Set, b: Self::Set) -> Self::Set {
+            std::array::from_fn(|i| std::array::from_fn(|j| a[i][0] * b[0][j] + a[i][1] * b[1][j]))
+        }
+    }
+
+    impl Identity for Matrix2 {
+        fn id() -> Self::Set {
+            [[M::new(1), M::new(0)], [M::new(0), M::new(1)]]
+        }
+    }
+
+    impl Inverse for Matrix2 {
+        fn inv([[a, b], [c, d]]: Self::Set) -> Self::Set {
+            let det = (a * d - b * c).inv().unwrap();
+            [[d * det, -b * det], [-c * det, a * det]]
+        }
+    }
+
+    /// 各頂点のポテンシャルを先に決め、それと整合する関係を追加していく。
+    fn check<T>(n: usize, mut random: impl FnMut() -> T::Set)
+    where
+        T: Group<Set: Clone + Eq + std::fmt::Debug>,
+    {
+        let mut rng = rand::rng();
+        let p = Vec::from_iter((0..n).map(|_| random()));
+        let expected = |s: usize, t: usize| T::op(T::inv(p[t].clone()), p[s].clone());
+
+        let mut uf = UnionFindWithPotential::<T>::new(n);
+        let mut label = Vec::from_iter(0..n);
+        for _ in 0..3 * n {
+            let (s, t) = (rng.random_range(0..n), rng.random_range(0..n));
+            let (ls, lt) = (label[s], label[t]);
+            if ls == lt {
+                assert_eq!(uf.potential(s, t), Some(expected(s, t)));
+                let w = random();
+                let res = if w == expected(s, t) {
+                    Ok(false)
+                } else {
+                    Err(expected(s, t))
+                };
+                assert_eq!(uf.union(s, t, w), res);
+                assert_eq!(uf.union(s, t, expected(s, t)), Ok(false));
+            } else {
+                assert_eq!(uf.potential(s, t), None);
+                assert_eq!(uf.union(s, t, expected(s, t)), Ok(true));
+                label.iter_mut().filter(|l| **l == lt).for_each(|l| *l = ls);
+            }
+            assert!(uf.same(s, t));
+            assert_eq!(uf.size(s), label.iter().filter(|&&l| l == ls).count());
+        }
+    }
+
+    #[test]
+    fn matrix() {
+        let mut rng = rand::rng();
+        for n in 1..50 {
+            check::<Matrix2>(n, || loop {
+                let a: [[M; 2]; 2] = std::array::from_fn(|_| {
+                    std::array::from_fn(|_| M::new(rng.random_range(0..3)))
+                });
+                if a[0][0] * a[1][1] != a[0][1] * a[1][0] {
+                    break a;
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn permutation() {
+        let mut rng = rand::rng();
+        for n in 1..50 {
+            check::<Permutation>(n, || {
+                let mut f = Permutation::id();
+                f.shuffle(&mut rng);
+                f
+            });
+        }
+    }
+}
@@ -0,0 +1,664 @@
+use std::{
+    fmt::Debug,
+    ops::{Bound, Range, RangeBounds},
+};
+
+use fenwick::FenwickTree;
+use num_traits::{PrimInt, Unsigned};
+use ops::{marker::Commutative, Group};
+
+/// 簡潔ビットベクトル。*O*(1) で rank を計算する。
+#[derive(Debug, Clone)]
+struct BitVector {
+    /// 末尾に番兵のワードを置く。`words.len() = N / 64 + 1`
+    words: Box<[u64]>,
+    /// `ranks[k]`は`words[..k]`に含まれる 1 の個数
+    ranks: Box<[usize]>,
+}
+
+impl BitVector {
+    fn new(bits: impl ExactSizeIterator<Item = bool>) -> Self {
+        let mut words = vec![0; bits.len() / 64 + 1];
+        for (i, b) in bits.enumerate() {
+            words[i / 64] |= (b as u64) << (i % 64)
+        }
+
+        let mut ranks = Vec::with_capacity(words.len());
+        let mut sum = 0;
+        for w in &words {
+            ranks.push(sum);
+            sum += w.count_ones() as usize
+        }
+
+        Self {
+            words: words.into_boxed_slice(),
+            ranks: ranks.into_boxed_slice(),
+        }
+    }
+
+    #[inline]
+    fn get(&self, i: usize) -> bool {
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// `..i`に含まれる 1 の個数
+    #[inline]
+    fn rank1(&self, i: usize) -> usize {
+        self.ranks[i / 64] + (self.words[i / 64] & ((1 << (i % 64)) - 1)).count_ones() as usize
+    }
+
+    /// `..i`に含まれる 0 の個数
+    #[inline]
+    fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+}
+
+/// 上位ビットから順に、ビットが 0 の要素を前に、1 の要素を後ろに安定ソートした結果を記録する。
+#[derive(Debug, Clone)]
+struct Level {
+    bits: BitVector,
+    zeros: usize,
+}
+
+impl Level {
+    /// 区間`l..r`の要素が、次のレベルで移る区間を返す。
+    #[inline]
+    fn next(&self, Range { start: l, end: r }: Range<usize>, bit: bool) -> Range<usize> {
+        let [l0, r0] = [self.bits.rank0(l), self.bits.rank0(r)];
+        if bit {
+            self.zeros + (l - l0)..self.zeros + (r - r0)
+        } else {
+            l0..r0
+        }
+    }
+}
+
+fn parse_range<R>(range: R, n: usize) -> Range<usize>
+where
+    R: RangeBounds<usize>,
+{
+    let l = match range.start_bound() {
+        Bound::Included(&l) => l,
+        Bound::Excluded(&l) => l + 1,
+        Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(&r) => r + 1,
+        Bound::Excluded(&r) => r,
+        Bound::Unbounded => n,
+    };
+    assert!(l <= r && r <= n, "index out of bounds");
+
+    l..r
+}
+
+/// 値の範囲`lo..hi`を返す。`None`は上限がないことを表す。
+fn parse_value_range<T, R>(range: R) -> [Option<T>; 2]
+where
+    T: PrimInt + Unsigned,
+    R: RangeBounds<T>,
+{
+    let lo = match range.start_bound() {
+        Bound::Included(&lo) => Some(lo),
+        Bound::Excluded(&lo) => lo.checked_add(&T::one()),
+        Bound::Unbounded => Some(T::zero()),
+    };
+    let hi = match range.end_bound() {
+        Bound::Included(&hi) => hi.checked_add(&T::one()),
+        Bound::Excluded(&hi) => Some(hi),
+        Bound::Unbounded => None,
+    };
+
+    [lo, hi]
+}
+
+/// 非負整数列に対して、区間内の順序統計量を計算するデータ構造。
+///
+/// 値の種類が多い場合は、`compress`で座標圧縮すると段数が減る。
+///
+/// # Example
+///
+/// ```
+/// use compress::compress;
+/// use wavelet_matrix::WaveletMatrix;
+///
+/// let a = vec![1_000_000_000, 3, 3, 100, 7];
+/// let (compressed, restore) = compress::<0>(&a);
+/// let wm = WaveletMatrix::from(compressed);
+///
+/// assert_eq!(restore[wm.kth_smallest(1..5, 2).unwrap() as usize], 7);
+/// assert_eq!(restore[wm.kth_largest(.., 0).unwrap() as usize], 1_000_000_000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WaveletMatrix<T> {
+    /// 上位ビットから順に並べる。`levels.len()`は最大値のビット長である。
+    levels: Box<[Level]>,
+    net_len: usize,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T> WaveletMatrix<T>
+where
+    T: PrimInt + Unsigned,
+{
+    pub fn len(&self) -> usize {
+        self.net_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.net_len == 0
+    }
+
+    /// 各段とその段が表すビットを上位から順に返す。
+    fn levels(&self) -> impl Iterator<Item = (&Level, usize)> {
+        self.levels.iter().zip((0..self.levels.len()).rev())
+    }
+
+    /// `x`の`bit`番目のビット
+    #[inline]
+    fn bit(x: T, bit: usize) -> bool {
+        (x >> bit) & T::one() == T::one()
+    }
+
+    /// `i`番目の要素を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn access(&self, mut i: usize) -> T {
+        assert!(i < self.net_len, "index out of bounds");
+
+        let mut x = T::zero();
+        for (level, bit) in self.levels() {
+            let b = level.bits.get(i);
+            if b {
+                x = x | T::one() << bit;
+            }
+            i = level.next(i..i, b).start;
+        }
+
+        x
+    }
+
+    /// 区間内で`k`番目 (0-indexed) に小さい値を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V*)
+    pub fn kth_smallest<R>(&self, range: R, mut k: usize) -> Option<T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let mut range = parse_range(range, self.net_len);
+        if k >= range.len() {
+            return None;
+        }
+
+        let mut x = T::zero();
+        for (level, bit) in self.levels() {
+            let zeros = level.next(range.clone(), false);
+            if k < zeros.len() {
+                range = zeros;
+            } else {
+                k -= zeros.len();
+                x = x | T::one() << bit;
+                range = level.next(range, true);
+            }
+        }
+
+        Some(x)
+    }
+
+    /// 区間内で`k`番目 (0-indexed) に大きい値を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V*)
+    pub fn kth_largest<R>(&self, range: R, k: usize) -> Option<T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = parse_range(range, self.net_len);
+        let k = range.len().checked_sub(k + 1)?;
+
+        self.kth_smallest(range, k)
+    }
+
+    /// 区間内で`upper`未満の値の個数を返す。`None`は上限がないことを表す。
+    fn count_less_inner(&self, mut range: Range<usize>, upper: Option<T>) -> usize {
+        let Some(upper) = upper else {
+            return range.len();
+        };
+        if self.levels.len() < T::zero().count_zeros() as usize
+            && upper >> self.levels.len() != T::zero()
+        {
+            return range.len();
+        }
+
+        let mut count = 0;
+        for (level, bit) in self.levels() {
+            if Self::bit(upper, bit) {
+                count += level.next(range.clone(), false).len();
+                range = level.next(range, true);
+            } else {
+                range = level.next(range, false);
+            }
+        }
+
+        count
+    }
+
+    /// 区間内で`upper`未満の値の個数を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V*)
+    pub fn count_less<R>(&self, range: R, upper: T) -> usize
+    where
+        R: RangeBounds<usize>,
+    {
+        self.count_less_inner(parse_range(range, self.net_len), Some(upper))
+    }
+
+    /// 区間内で値が`value_range`に含まれる要素の個数を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V*)
+    pub fn count_in_value_range<R, V>(&self, range: R, value_range: V) -> usize
+    where
+        R: RangeBounds<usize>,
+        V: RangeBounds<T>,
+    {
+        let range = parse_range(range, self.net_len);
+        let [lo, hi] = parse_value_range(value_range);
+        if lo.is_none() || hi.is_some_and(|hi| Some(hi) <= lo) {
+            return 0;
+        }
+
+        self.count_less_inner(range.clone(), hi) - self.count_less_inner(range, lo)
+    }
+
+    /// 区間内で`x`と等しい値の個数を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V*)
+    pub fn count<R>(&self, range: R, x: T) -> usize
+    where
+        R: RangeBounds<usize>,
+    {
+        self.count_in_value_range(range, x..=x)
+    }
+
+    /// 区間内で`upper`未満の最大の値を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V*)
+    pub fn prev_value<R>(&self, range: R, upper: T) -> Option<T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = parse_range(range, self.net_len);
+        let k = self
+            .count_less_inner(range.clone(), Some(upper))
+            .checked_sub(1)?;
+
+        self.kth_smallest(range, k)
+    }
+
+    /// 区間内で`lower`以上の最小の値を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V*)
+    pub fn next_value<R>(&self, range: R, lower: T) -> Option<T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = parse_range(range, self.net_len);
+        let k = self.count_less_inner(range.clone(), Some(lower));
+
+        self.kth_smallest(range, k)
+    }
+}
+
+impl<T> From<Vec<T>> for WaveletMatrix<T>
+where
+    T: PrimInt + Unsigned,
+{
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N* log *V*)
+    fn from(mut values: Vec<T>) -> Self {
+        let max = values.iter().copied().fold(T::zero(), T::max);
+        let height = (max.count_zeros() + max.count_ones() - max.leading_zeros()) as usize;
+
+        let net_len = values.len();
+        let mut levels = Vec::with_capacity(height);
+        let mut ones = Vec::with_capacity(net_len);
+        for bit in (0..height).rev() {
+            let bits = BitVector::new(values.iter().map(|&x| Self::bit(x, bit)));
+
+            // 安定ソート
+            values.retain(|&x| {
+                let b = Self::bit(x, bit);
+                if b {
+                    ones.push(x)
+                }
+                !b
+            });
+            let zeros = values.len();
+            values.append(&mut ones);
+
+            levels.push(Level { bits, zeros });
+        }
+
+        Self {
+            levels: levels.into_boxed_slice(),
+            net_len,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+/// 各要素に重みをもたせた[`WaveletMatrix`]。
+/// 区間内で値が指定された範囲にある要素の重みの総和を計算する。重みは一点更新できる。
+pub struct WaveletMatrixWithSum<T, G>
+where
+    G: Group + Commutative,
+{
+    matrix: WaveletMatrix<T>,
+    /// `sums[d]`は`d`段目で並べ替えた後の順序で重みを管理する。
+    sums: Box<[FenwickTree<G>]>,
+    /// 段数が 0 のときに利用する
+    weights: FenwickTree<G>,
+}
+
+impl<T, G> WaveletMatrixWithSum<T, G>
+where
+    T: PrimInt + Unsigned,
+    G: Group<Set: Copy> + Commutative,
+{
+    pub fn len(&self) -> usize {
+        self.matrix.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matrix.is_empty()
+    }
+
+    /// 値の列を管理する[`WaveletMatrix`]を返す。
+    pub fn matrix(&self) -> &WaveletMatrix<T> {
+        &self.matrix
+    }
+
+    /// `i`番目の要素の重みに`additional`を加える。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V* log *N*)
+    ///
+    /// # Panics
+    ///
+    /// - `i` should be less than `N`
+    pub fn point_update(&mut self, mut i: usize, additional: G::Set) {
+        assert!(i < self.len(), "index out of bounds");
+
+        self.weights.point_update(i, additional);
+        for ((level, _), sum) in self.matrix.levels().zip(self.sums.iter_mut()) {
+            i = level.next(i..i, level.bits.get(i)).start;
+            sum.point_update(i, additional);
+        }
+    }
+
+    /// 区間内で`upper`未満の値をもつ要素の重みの総和を返す。`None`は上限がないことを表す。
+    fn sum_less_inner(&self, mut range: Range<usize>, upper: Option<T>) -> G::Set {
+        let Some(upper) = upper else {
+            return self.weights.range_query(range);
+        };
+        let height = self.matrix.levels.len();
+        if height < T::zero().count_zeros() as usize && upper >> height != T::zero() {
+            return self.weights.range_query(range);
+        }
+
+        let mut sum = G::id();
+        for ((level, bit), fenwick) in self.matrix.levels().zip(self.sums.iter()) {
+            let zeros = level.next(range.clone(), false);
+            if WaveletMatrix::bit(upper, bit) {
+                sum = G::op(sum, fenwick.range_query(zeros));
+                range = level.next(range, true);
+            } else {
+                range = zeros;
+            }
+        }
+
+        sum
+    }
+
+    /// 区間内で`upper`未満の値をもつ要素の重みの総和を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V* log *N*)
+    pub fn sum_less<R>(&self, range: R, upper: T) -> G::Set
+    where
+        R: RangeBounds<usize>,
+    {
+        self.sum_less_inner(parse_range(range, self.len()), Some(upper))
+    }
+
+    /// 区間内で値が`value_range`に含まれる要素の重みの総和を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(log *V* log *N*)
+    pub fn sum_in_value_range<R, V>(&self, range: R, value_range: V) -> G::Set
+    where
+        R: RangeBounds<usize>,
+        V: RangeBounds<T>,
+    {
+        let range = parse_range(range, self.len());
+        let [lo, hi] = parse_value_range(value_range);
+        if lo.is_none() || hi.is_some_and(|hi| Some(hi) <= lo) {
+            return G::id();
+        }
+
+        G::op(
+            G::inv(self.sum_less_inner(range.clone(), lo)),
+            self.sum_less_inner(range, hi),
+        )
+    }
+}
+
+impl<T, G> Clone for WaveletMatrixWithSum<T, G>
+where
+    T: Clone,
+    G: Group + Commutative,
+    FenwickTree<G>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            matrix: self.matrix.clone(),
+            sums: self.sums.clone(),
+            weights: self.weights.clone(),
+        }
+    }
+}
+
+impl<T, G> Debug for WaveletMatrixWithSum<T, G>
+where
+    T: Debug,
+    G: Group + Commutative,
+    FenwickTree<G>: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WaveletMatrixWithSum")
+            .field("matrix", &self.matrix)
+            .field("sums", &self.sums)
+            .field("weights", &self.weights)
+            .finish()
+    }
+}
+
+impl<T, G> From<Vec<(T, G::Set)>> for WaveletMatrixWithSum<T, G>
+where
+    T: PrimInt + Unsigned,
+    G: Group<Set: Copy> + Commutative,
+{
+    /// `(値, 重み)`の列から構築する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N* log *V*)
+    fn from(values: Vec<(T, G::Set)>) -> Self {
+        let (values, weights): (Vec<_>, Vec<_>) = values.into_iter().unzip();
+        let matrix = WaveletMatrix::from(values);
+
+        // 各段で並べ替えた後の位置を追跡する
+        let mut order = Vec::from_iter(0..matrix.len());
+        let mut next = vec![0; matrix.len()];
+        let mut sums = Vec::with_capacity(matrix.levels.len());
+        for (level, _) in matrix.levels() {
+            for (j, &i) in order.iter().enumerate() {
+                next[level.next(j..j, level.bits.get(j)).start] = i;
+            }
+            std::mem::swap(&mut order, &mut next);
+            sums.push(FenwickTree::from_iter(order.iter().map(|&i| weights[i])));
+        }
+
+        Self {
+            matrix,
+            sums: sums.into_boxed_slice(),
+            weights: FenwickTree::from(weights),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ops::ops::Additive;
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn random() {
+        let mut rng = rand::rng();
+        for (n, max) in [
+            (0, 1),
+            (1, 1),
+            (10, 1),
+            (30, 4),
+            (100, 100),
+            (100, u32::MAX),
+        ] {
+            let a = Vec::from_iter((0..n).map(|_| rng.random_range(0..max)));
+            let wm = WaveletMatrix::from(a.clone());
+
+            for (i, &x) in a.iter().enumerate() {
+                assert_eq!(wm.access(i), x);
+            }
+
+            for _ in 0..1000 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                let mut sorted = a[l..r].to_vec();
+                sorted.sort_unstable();
+
+                let k = rng.random_range(0..=r - l);
+                assert_eq!(wm.kth_smallest(l..r, k), sorted.get(k).copied());
+                assert_eq!(wm.kth_largest(l..r, k), sorted.iter().rev().nth(k).copied());
+
+                let x = if rng.random_bool(0.5) && l < r {
+                    a[rng.random_range(l..r)]
+                } else {
+                    rng.random_range(0..max)
+                };
+                let y = rng.random_range(0..max);
+                assert_eq!(wm.count_less(l..r, x), sorted.partition_point(|&v| v < x));
+                assert_eq!(
+                    wm.count(l..r, x),
+                    sorted.iter().filter(|&&v| v == x).count()
+                );
+                assert_eq!(
+                    wm.count_in_value_range(l..r, x..=y),
+                    sorted.iter().filter(|&&v| (x..=y).contains(&v)).count()
+                );
+                assert_eq!(
+                    wm.count_in_value_range(l..r, x..),
+                    sorted.iter().filter(|&&v| v >= x).count()
+                );
+                assert_eq!(
+                    wm.prev_value(l..r, x),
+                    sorted.iter().rev().find(|&&v| v < x).copied()
+                );
+                assert_eq!(
+                    wm.next_value(l..r, x),
+                    sorted.iter().find(|&&v| v >= x).copied()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn extreme_values() {
+        let a = vec![u64::MAX, 0, u64::MAX - 1, 1 << 63];
+        let wm = WaveletMatrix::from(a);
+
+        assert_eq!(wm.count_in_value_range(.., ..), 4);
+        assert_eq!(wm.count_in_value_range(.., u64::MAX..), 1);
+        assert_eq!(
+            wm.count_in_value_range(.., (Bound::Excluded(u64::MAX), Bound::Unbounded)),
+            0
+        );
+        assert_eq!(wm.count_less(.., u64::MAX), 3);
+        assert_eq!(wm.next_value(1.., 1), Some(1 << 63));
+        assert_eq!(wm.prev_value(.., u64::MAX), Some(u64::MAX - 1));
+        assert_eq!(wm.kth_largest(.., 0), Some(u64::MAX));
+    }
+
+    #[test]
+    fn with_sum() {
+        let mut rng = rand::rng();
+        for (n, max) in [(0, 1), (1, 1), (30, 4), (100, 100u32)] {
+            let a = Vec::from_iter((0..n).map(|_| rng.random_range(0..max)));
+            let mut w = Vec::from_iter((0..n).map(|_| rng.random_range(-100..100)));
+            let mut wm = WaveletMatrixWithSum::<u32, Additive<i64>>::from(Vec::from_iter(
+                a.iter().copied().zip(w.iter().copied()),
+            ));
+
+            for _ in 0..1000 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                if n > 0 && rng.random_bool(0.3) {
+                    let i = rng.random_range(0..n);
+                    let v = rng.random_range(-100..100);
+                    w[i] += v;
+                    wm.point_update(i, v);
+                }
+
+                let x = rng.random_range(0..max);
+                let y = rng.random_range(0..=max);
+                assert_eq!(
+                    wm.sum_less(l..r, x),
+                    (l..r).filter(|&i| a[i] < x).map(|i| w[i]).sum::<i64>()
+                );
+                assert_eq!(
+                    wm.sum_in_value_range(l..r, x..y),
+                    (l..r)
+                        .filter(|&i| (x..y).contains(&a[i]))
+                        .map(|i| w[i])
+                        .sum::<i64>()
+                );
+                assert_eq!(wm.sum_in_value_range(l..r, ..), w[l..r].iter().sum::<i64>());
+            }
+        }
+    }
+}
@@ -0,0 +1,496 @@
+use std::{
+    fmt::Debug,
+    ops::{Index, IndexMut, Mul},
+};
+
+use ops::Semiring;
+
+/// 半環`S`上の`N`行`M`列の行列
+///
+/// 行列の積は可換でないので、`Multiplicative<Matrix<..>>`は可換性を要求する構造には使えない。
+///
+/// ```compile_fail
+/// use matrix::Matrix;
+/// use ops::{marker::Commutative, ops::{Arithmetic, Multiplicative}};
+///
+/// fn assert_commutative<T: Commutative>() {}
+/// assert_commutative::<Multiplicative<Matrix<Arithmetic<i64>, 2, 2>>>();
+/// ```
+pub struct Matrix<S, const N: usize, const M: usize>
+where
+    S: Semiring,
+{
+    data: [[S::Set; M]; N],
+}
+
+impl<S, const N: usize, const M: usize> Matrix<S, N, M>
+where
+    S: Semiring<Set: Copy>,
+{
+    /// 零行列
+    pub fn zero() -> Self {
+        Self {
+            data: [[S::zero(); M]; N],
+        }
+    }
+
+    /// 転置行列を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NM*)
+    pub fn transpose(&self) -> Matrix<S, M, N> {
+        let mut res = Matrix::zero();
+        for i in 0..N {
+            for j in 0..M {
+                res.data[j][i] = self.data[i][j]
+            }
+        }
+
+        res
+    }
+
+    /// 列ベクトル`v`に左から作用させる。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NM*)
+    pub fn apply(&self, v: [S::Set; M]) -> [S::Set; N] {
+        let mut res = [S::zero(); N];
+        for (res, row) in res.iter_mut().zip(self.data.iter()) {
+            for (a, b) in row.iter().zip(v.iter()) {
+                *res = S::add(*res, S::mul(*a, *b))
+            }
+        }
+
+        res
+    }
+}
+
+impl<S, const N: usize> Matrix<S, N, N>
+where
+    S: Semiring<Set: Copy>,
+{
+    /// 単位行列
+    pub fn identity() -> Self {
+        let mut res = Self::zero();
+        for i in 0..N {
+            res.data[i][i] = S::one()
+        }
+
+        res
+    }
+
+    /// `exp`乗を計算する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*^3 log `exp`)
+    pub fn pow(mut self, mut exp: u64) -> Self {
+        let mut res = Self::identity();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res = res * self;
+            }
+            self = self * self;
+            exp >>= 1;
+        }
+
+        res
+    }
+}
+
+impl<S, const N: usize, const M: usize, const K: usize> Mul<Matrix<S, M, K>> for Matrix<S, N, M>
+where
+    S: Semiring<Set: Copy>,
+{
+    type Output = Matrix<S, N, K>;
+
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NMK*)
+    fn mul(self, rhs: Matrix<S, M, K>) -> Self::Output {
+        let mut res = Matrix::zero();
+        for (res, row) in res.data.iter_mut().zip(self.data.iter()) {
+            for (a, rhs) in row.iter().zip(rhs.data.iter()) {
+                for (res, b) in res.iter_mut().zip(rhs.iter()) {
+                    *res = S::add(*res, S::mul(*a, *b))
+                }
+            }
+        }
+
+        res
+    }
+}
+
+impl<S, const N: usize, const M: usize> From<[[S::Set; M]; N]> for Matrix<S, N, M>
+where
+    S: Semiring,
+{
+    fn from(data: [[S::Set; M]; N]) -> Self {
+        Self { data }
+    }
+}
+
+impl<S, const N: usize, const M: usize> Clone for Matrix<S, N, M>
+where
+    S: Semiring<Set: Copy>,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, const N: usize, const M: usize> Copy for Matrix<S, N, M> where S: Semiring<Set: Copy> {}
+
+impl<S, const N: usize, const M: usize> PartialEq for Matrix<S, N, M>
+where
+    S: Semiring<Set: PartialEq>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<S, const N: usize, const M: usize> Eq for Matrix<S, N, M> where S: Semiring<Set: Eq> {}
+
+impl<S, const N: usize, const M: usize> Debug for Matrix<S, N, M>
+where
+    S: Semiring<Set: Debug>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.data.iter()).finish()
+    }
+}
+
+impl<S, const N: usize, const M: usize> Index<usize> for Matrix<S, N, M>
+where
+    S: Semiring,
+{
+    type Output = [S::Set; M];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl<S, const N: usize, const M: usize> IndexMut<usize> for Matrix<S, N, M>
+where
+    S: Semiring,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.data[index]
+    }
+}
+
+/// 半環`S`上の可変サイズの行列
+pub struct DynMatrix<S>
+where
+    S: Semiring,
+{
+    /// row-major
+    data: Box<[S::Set]>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<S> DynMatrix<S>
+where
+    S: Semiring<Set: Copy>,
+{
+    /// `rows`行`cols`列の零行列
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NM*)
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        Self {
+            data: vec![S::zero(); rows.checked_mul(cols).unwrap()].into_boxed_slice(),
+            rows,
+            cols,
+        }
+    }
+
+    /// `n`次の単位行列
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*^2)
+    pub fn identity(n: usize) -> Self {
+        let mut res = Self::zero(n, n);
+        for i in 0..n {
+            res[i][i] = S::one()
+        }
+
+        res
+    }
+
+    /// `(i, j)`成分を`f(i, j)`で初期化する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NM*)
+    pub fn from_fn<F>(rows: usize, cols: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> S::Set,
+    {
+        Self {
+            data: Box::from_iter(
+                (0..rows)
+                    .flat_map(|i| (0..cols).map(move |j| (i, j)))
+                    .map(|(i, j)| f(i, j)),
+            ),
+            rows,
+            cols,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// 転置行列を返す。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NM*)
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.cols, self.rows, |i, j| self[j][i])
+    }
+
+    /// 列ベクトル`v`に左から作用させる。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NM*)
+    ///
+    /// # Panics
+    ///
+    /// - `v.len()` should be equal to the number of columns
+    pub fn apply(&self, v: &[S::Set]) -> Vec<S::Set> {
+        assert_eq!(self.cols, v.len(), "dimension mismatch");
+
+        Vec::from_iter((0..self.rows).map(|i| {
+            self[i]
+                .iter()
+                .zip(v)
+                .fold(S::zero(), |acc, (a, b)| S::add(acc, S::mul(*a, *b)))
+        }))
+    }
+
+    /// `exp`乗を計算する。
+    ///
+    /// # Time Complexity
+    ///
+    /// *Θ*(*N*^3 log `exp`)
+    ///
+    /// # Panics
+    ///
+    /// - `self` should be a square matrix
+    pub fn pow(&self, mut exp: u64) -> Self {
+        assert_eq!(self.rows, self.cols, "not a square matrix");
+
+        let mut res = Self::identity(self.rows);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res = &res * &base;
+            }
+            base = &base * &base;
+            exp >>= 1;
+        }
+
+        res
+    }
+}
+
+impl<S> Mul for &DynMatrix<S>
+where
+    S: Semiring<Set: Copy>,
+{
+    type Output = DynMatrix<S>;
+
+    /// # Time Complexity
+    ///
+    /// *Θ*(*NMK*)
+    ///
+    /// # Panics
+    ///
+    /// - `self.cols()` should be equal to `rhs.rows()`
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.cols, rhs.rows, "dimension mismatch");
+
+        let mut res = DynMatrix::zero(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                let a = self[i][j];
+                for (res, b) in res[i].iter_mut().zip(rhs[j].iter()) {
+                    *res = S::add(*res, S::mul(a, *b))
+                }
+            }
+        }
+
+        res
+    }
+}
+
+impl<S, const N: usize, const M: usize> From<Matrix<S, N, M>> for DynMatrix<S>
+where
+    S: Semiring<Set: Copy>,
+{
+    fn from(value: Matrix<S, N, M>) -> Self {
+        Self::from_fn(N, M, |i, j| value[i][j])
+    }
+}
+
+impl<S> Clone for DynMatrix<S>
+where
+    S: Semiring<Set: Clone>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+}
+
+impl<S> PartialEq for DynMatrix<S>
+where
+    S: Semiring<Set: PartialEq>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        (self.rows, self.cols) == (other.rows, other.cols) && self.data == other.data
+    }
+}
+
+impl<S> Eq for DynMatrix<S> where S: Semiring<Set: Eq> {}
+
+impl<S> Debug for DynMatrix<S>
+where
+    S: Semiring<Set: Debug>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.data.chunks(self.cols.max(1)))
+            .finish()
+    }
+}
+
+impl<S> Index<usize> for DynMatrix<S>
+where
+    S: Semiring,
+{
+    type Output = [S::Set];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index * self.cols..][..self.cols]
+    }
+}
+
+impl<S> IndexMut<usize> for DynMatrix<S>
+where
+    S: Semiring,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.data[index * self.cols..][..self.cols]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mint::Mint;
+    use ops::ops::{Arithmetic, Boolean, MinPlus};
+    use rand::Rng;
+
+    use super::*;
+
+    type M = Mint<998244353>;
+
+    #[test]
+    fn fibonacci() {
+        let fib =
+            Matrix::<Arithmetic<M>, 2, 2>::from([[M::new(1), M::new(1)], [M::new(1), M::new(0)]]);
+        let dyn_fib = DynMatrix::from(fib);
+
+        let mut naive = [M::new(0), M::new(1)];
+        for n in 0..100 {
+            assert_eq!(fib.pow(n).apply([M::new(1), M::new(0)])[1], naive[0]);
+            assert_eq!(dyn_fib.pow(n).apply(&[M::new(1), M::new(0)])[1], naive[0]);
+            naive = [naive[1], naive[0] + naive[1]];
+        }
+    }
+
+    #[test]
+    fn k_step_shortest_path() {
+        const N: usize = 6;
+
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let mut graph = Matrix::<MinPlus<i64>, N, N>::zero();
+            for i in 0..N {
+                for j in 0..N {
+                    if rng.random_bool(0.5) {
+                        graph[i][j] = rng.random_range(-10..100)
+                    }
+                }
+            }
+            let dyn_graph = DynMatrix::from(graph);
+
+            // dist[v] = k 辺を通って 0 から v に到達する最短路長
+            let mut dist = [i64::MAX; N];
+            dist[0] = 0;
+            for k in 0..20 {
+                let res = graph.transpose().pow(k).apply({
+                    let mut v = [i64::MAX; N];
+                    v[0] = 0;
+                    v
+                });
+                assert_eq!(res, dist);
+                assert_eq!(dyn_graph.pow(k)[0], dist);
+
+                let mut next = [i64::MAX; N];
+                for (i, &d) in dist.iter().enumerate() {
+                    for (next, &w) in next.iter_mut().zip(graph[i].iter()) {
+                        if d != i64::MAX && w != i64::MAX {
+                            *next = (*next).min(d + w)
+                        }
+                    }
+                }
+                dist = next;
+            }
+        }
+    }
+
+    #[test]
+    fn reachability() {
+        let mut rng = rand::rng();
+        for n in 1..20 {
+            let graph = DynMatrix::<Boolean>::from_fn(n, n, |_, _| rng.random_bool(0.1));
+
+            // 推移閉包を Warshall-Floyd 法で計算する
+            let mut closure = DynMatrix::<Boolean>::from_fn(n, n, |i, j| i == j || graph[i][j]);
+            for k in 0..n {
+                for i in 0..n {
+                    for j in 0..n {
+                        closure[i][j] |= closure[i][k] && closure[k][j]
+                    }
+                }
+            }
+
+            let reflexive = DynMatrix::<Boolean>::from_fn(n, n, |i, j| i == j || graph[i][j]);
+            assert_eq!(reflexive.pow(n as u64), closure);
+            assert_eq!(
+                (&reflexive * &DynMatrix::identity(n))
+                    .transpose()
+                    .transpose(),
+                reflexive
+            );
+        }
+    }
+}
@@ -1,11 +1,21 @@
 use std::{
     fmt::Display,
-    ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 
 use num_integer::{ExtendedGcd, Integer};
 use num_traits::{One, Zero};
 
+/// `MOD`を法とする剰余類
+///
+/// [`ops::Field`]は素数であることが分かっている法にだけ実装される。
+///
+/// ```compile_fail
+/// use mint::Mint;
+///
+/// fn assert_field<T: ops::Field>() {}
+/// assert_field::<Mint<6>>();
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Mint<const MOD: u32>(pub u64);
 
@@ -95,11 +105,31 @@ impl<const MOD: u32> MulAssign for Mint<MOD> {
     }
 }
 
+/// `MOD`が素数でない場合、`rhs`は`MOD`と互いに素である必要がある。
+///
+/// # Panics
+///
+/// - `rhs` should be invertible
+impl<const MOD: u32> Div for Mint<MOD> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inv().expect("division by non-invertible element")
+    }
+}
+
+impl<const MOD: u32> DivAssign for Mint<MOD> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs
+    }
+}
+
 impl<const MOD: u32> Neg for Mint<MOD> {
     type Output = Self;
 
     fn neg(self) -> Self::Output {
-        Self(MOD as u64 - self.0)
+        Self((MOD as u64 - self.0) % MOD as u64)
     }
 }
 
@@ -119,6 +149,17 @@ impl<const MOD: u32> One for Mint<MOD> {
     }
 }
 
+// 一般の`MOD`では体をなさないので、素数であることが分かっている法にだけ実装する。
+macro_rules! prime_field_impl {
+    ($( $p:literal )+) => {
+        $( impl ops::Field for Mint<$p> {} )+
+
+        #[cfg(test)]
+        const FIELD_PRIMES: &[u32] = &[$( $p ),+];
+    };
+}
+prime_field_impl!( 998244353 1000000007 1000000009 167772161 469762049 754974721 );
+
 impl<T, const MOD: u32> From<T> for Mint<MOD>
 where
     T: Into<u32>,
@@ -127,3 +168,34 @@ where
         Self::new(value.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ::ops::{ops::Affine, testing};
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn field_primes() {
+        for &p in FIELD_PRIMES {
+            assert!((2..).take_while(|d| d * d <= p).all(|d| p % d != 0), "{p}");
+        }
+    }
+
+    #[test]
+    fn field() {
+        type M = Mint<998244353>;
+
+        let rng = &mut rand::rng();
+        testing::check_field::<M, _, _>(rng, 1000, |rng| M::new(rng.random_range(0..5))).unwrap();
+        testing::check_field::<M, _, _>(rng, 1000, |rng| M::new(rng.random())).unwrap();
+        testing::check_group::<Affine<M>, _, _>(rng, 1000, |rng| {
+            (
+                M::new(rng.random_range(1..998244353)),
+                M::new(rng.random_range(0..3)),
+            )
+        })
+        .unwrap();
+    }
+}
@@ -23,6 +23,100 @@ pub trait Group: Monoid + Inverse {}
 
 impl<T: Monoid + Inverse> Group for T {}
 
+/// 四則演算が体をなす数の型。
+///
+/// 整数型は`1 / x`が乗法逆元にならないので実装しない。
+pub trait Field:
+    Copy
+    + num_traits::Zero
+    + num_traits::One
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<Output = Self>
+    + std::ops::Div<Output = Self>
+    + std::ops::Neg<Output = Self>
+{
+}
+
+impl Field for f32 {}
+impl Field for f64 {}
+
+/// 作用素モノイド`Map`のモノイド`Arg`への作用。
+/// 下記の条件を満たすとき、遅延伝搬に利用できる。
+///
+/// - ∀ f, g ∈ Map, ∀ x ∈ Arg, act(f * g, x) = act(g, act(f, x))
+/// - ∀ x ∈ Arg, act(e, x) = x
+/// - ∀ f ∈ Map, ∀ x, y ∈ Arg, act(f, x + y) = act(f, x) + act(f, y)
+///
+/// 作用素は時系列順に合成する。つまり、`f * g`は`f`を作用させてから`g`を作用させる作用素である。
+pub trait MonoidAction {
+    type Map: Monoid;
+    type Arg: Monoid;
+
+    fn act(
+        f: <Self::Map as SemiGroup>::Set,
+        x: <Self::Arg as SemiGroup>::Set,
+    ) -> <Self::Arg as SemiGroup>::Set;
+}
+
+/// 区間幅に依存する作用。`size`は`x`が表す区間の幅である。
+/// 分配法則は act(f, x + y, a + b) = act(f, x, a) + act(f, y, b) となる。
+///
+/// [`MonoidAction`]は区間幅を無視する作用として自動実装される。
+pub trait MonoidActionWithSize {
+    type Map: Monoid;
+    type Arg: Monoid;
+
+    fn act_with_size(
+        f: <Self::Map as SemiGroup>::Set,
+        x: <Self::Arg as SemiGroup>::Set,
+        size: usize,
+    ) -> <Self::Arg as SemiGroup>::Set;
+}
+
+impl<A: MonoidAction> MonoidActionWithSize for A {
+    type Map = A::Map;
+    type Arg = A::Arg;
+
+    #[inline(always)]
+    fn act_with_size(
+        f: <Self::Map as SemiGroup>::Set,
+        x: <Self::Arg as SemiGroup>::Set,
+        _size: usize,
+    ) -> <Self::Arg as SemiGroup>::Set {
+        A::act(f, x)
+    }
+}
+
+/// 加法`Add`と乗法`Mul`からなる半環。下記の条件を満たす必要がある。
+///
+/// - `Add`は可換モノイド、`Mul`はモノイドである
+/// - ∀ a, b, c, a * (b + c) = a * b + a * c, (a + b) * c = a * c + b * c
+/// - ∀ a, 0 * a = a * 0 = 0
+pub trait Semiring {
+    type Set;
+    type Add: Monoid<Set = Self::Set> + marker::Commutative;
+    type Mul: Monoid<Set = Self::Set>;
+
+    /// 加法単位元
+    fn zero() -> Self::Set {
+        Self::Add::id()
+    }
+
+    /// 乗法単位元
+    fn one() -> Self::Set {
+        Self::Mul::id()
+    }
+
+    fn add(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+        Self::Add::op(lhs, rhs)
+    }
+
+    fn mul(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+        Self::Mul::op(lhs, rhs)
+    }
+}
+
 pub mod marker {
     use crate::SemiGroup;
 
@@ -33,6 +127,46 @@ pub mod marker {
     pub trait Commutative: SemiGroup {}
 }
 
+/// 演算の順序を入れ替えた双対。`Dual::<T>::op(x, y) = T::op(y, x)`が成り立つ。
+///
+/// 非可換な演算について、逆順に畳み込みたい場合に利用する。
+///
+/// # Example
+///
+/// ```
+/// use ops::{ops::Affine, Dual, SemiGroup};
+///
+/// let f = (2, 1); // x -> 2x + 1
+/// let g = (3, 4); // x -> 3x + 4
+///
+/// assert_eq!(Dual::<Affine<i64>>::op(f, g), Affine::<i64>::op(g, f));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Dual<T>(std::marker::PhantomData<T>);
+
+impl<T: SemiGroup> SemiGroup for Dual<T> {
+    type Set = T::Set;
+
+    fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+        T::op(rhs, lhs)
+    }
+}
+
+impl<T: Identity> Identity for Dual<T> {
+    fn id() -> Self::Set {
+        T::id()
+    }
+}
+
+impl<T: Inverse> Inverse for Dual<T> {
+    fn inv(x: Self::Set) -> Self::Set {
+        T::inv(x)
+    }
+}
+
+impl<T: marker::Commutative> marker::Commutative for Dual<T> {}
+impl<T: marker::Idempotent> marker::Idempotent for Dual<T> {}
+
 macro_rules! tuple_impl {
     ( ($t0:tt, $lhs0:tt, $rhs0:tt, $x0:tt), $( ($t:tt, $lhs:tt, $rhs:tt, $x:tt) ),+ $(,)? ) => {
         impl< $t0 :SemiGroup, $( $t : SemiGroup),+> SemiGroup for ( $t0, $( $t ),+)
@@ -84,21 +218,15 @@ mod unit {
     impl SemiGroup for () {
         type Set = ();
 
-        fn op(_: Self::Set, _: Self::Set) -> Self::Set {
-            ()
-        }
+        fn op(_: Self::Set, _: Self::Set) -> Self::Set {}
     }
 
     impl Identity for () {
-        fn id() -> Self::Set {
-            ()
-        }
+        fn id() -> Self::Set {}
     }
 
     impl Inverse for () {
-        fn inv(_: Self::Set) -> Self::Set {
-            ()
-        }
+        fn inv(_: Self::Set) -> Self::Set {}
     }
 
     impl Commutative for () {}
@@ -106,13 +234,14 @@ mod unit {
 }
 
 pub mod ops {
-    use num_traits::Zero;
+    use num_integer::Integer;
+    use num_traits::{One, Unsigned, Zero};
 
     use super::*;
 
     use std::{
         marker::PhantomData,
-        ops::{Add, Neg},
+        ops::{Add, Mul, Neg, Not},
     };
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -160,6 +289,18 @@ pub mod ops {
     }
     primitive_min_max_identity_impl!( u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize );
 
+    impl Identity for Min<bool> {
+        fn id() -> Self::Set {
+            true
+        }
+    }
+
+    impl Identity for Max<bool> {
+        fn id() -> Self::Set {
+            false
+        }
+    }
+
     /// `+`演算が定義された集合
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
     pub struct Additive<T>(PhantomData<T>);
@@ -196,4 +337,1148 @@ pub mod ops {
     }
 
     impl<T> marker::Commutative for Additive<T> where Additive<T>: SemiGroup {}
+
+    /// `*`演算が定義された集合
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Multiplicative<T>(PhantomData<T>);
+
+    impl<T> SemiGroup for Multiplicative<T>
+    where
+        T: Mul<Output = T>,
+    {
+        type Set = T;
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            lhs * rhs
+        }
+    }
+
+    impl<T> Identity for Multiplicative<T>
+    where
+        T: One,
+        Multiplicative<T>: SemiGroup<Set = T>,
+    {
+        fn id() -> Self::Set {
+            T::one()
+        }
+    }
+
+    // 行列のように非可換な積もあるので、可換性は型ごとに与える。
+    // 体は乗法について可換である。
+    impl<T: Field> marker::Commutative for Multiplicative<T> {}
+
+    macro_rules! primitive_multiplicative_commutative_impl {
+        ($( $t:ty )+) => {$(
+            impl marker::Commutative for Multiplicative<$t> {}
+        )+};
+    }
+    primitive_multiplicative_commutative_impl!( u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize );
+
+    /// ビット積
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct BitAnd<T>(PhantomData<T>);
+
+    impl<T> SemiGroup for BitAnd<T>
+    where
+        T: std::ops::BitAnd<Output = T>,
+    {
+        type Set = T;
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            lhs & rhs
+        }
+    }
+
+    impl<T> Identity for BitAnd<T>
+    where
+        T: Zero + Not<Output = T>,
+        BitAnd<T>: SemiGroup<Set = T>,
+    {
+        fn id() -> Self::Set {
+            !T::zero()
+        }
+    }
+
+    impl<T> marker::Idempotent for BitAnd<T> where BitAnd<T>: SemiGroup {}
+    impl<T> marker::Commutative for BitAnd<T> where BitAnd<T>: SemiGroup {}
+
+    /// ビット和
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct BitOr<T>(PhantomData<T>);
+
+    impl<T> SemiGroup for BitOr<T>
+    where
+        T: std::ops::BitOr<Output = T>,
+    {
+        type Set = T;
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            lhs | rhs
+        }
+    }
+
+    impl<T> Identity for BitOr<T>
+    where
+        T: Zero,
+        BitOr<T>: SemiGroup<Set = T>,
+    {
+        fn id() -> Self::Set {
+            T::zero()
+        }
+    }
+
+    impl<T> marker::Idempotent for BitOr<T> where BitOr<T>: SemiGroup {}
+    impl<T> marker::Commutative for BitOr<T> where BitOr<T>: SemiGroup {}
+
+    /// 排他的論理和
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct BitXor<T>(PhantomData<T>);
+
+    impl<T> SemiGroup for BitXor<T>
+    where
+        T: std::ops::BitXor<Output = T>,
+    {
+        type Set = T;
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            lhs ^ rhs
+        }
+    }
+
+    impl<T> Identity for BitXor<T>
+    where
+        T: Zero,
+        BitXor<T>: SemiGroup<Set = T>,
+    {
+        fn id() -> Self::Set {
+            T::zero()
+        }
+    }
+
+    impl<T> Inverse for BitXor<T>
+    where
+        BitXor<T>: Monoid<Set = T>,
+    {
+        fn inv(x: Self::Set) -> Self::Set {
+            x
+        }
+    }
+
+    impl<T> marker::Commutative for BitXor<T> where BitXor<T>: SemiGroup {}
+
+    /// 最大公約数。負の数では単位元の条件を満たさないので、符号なし整数に限る。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Gcd<T>(PhantomData<T>);
+
+    impl<T: Integer + Unsigned> SemiGroup for Gcd<T> {
+        type Set = T;
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            lhs.gcd(&rhs)
+        }
+    }
+
+    impl<T: Integer + Unsigned> Identity for Gcd<T> {
+        fn id() -> Self::Set {
+            T::zero()
+        }
+    }
+
+    impl<T: Integer + Unsigned> marker::Idempotent for Gcd<T> {}
+    impl<T: Integer + Unsigned> marker::Commutative for Gcd<T> {}
+
+    /// 最小公倍数。オーバーフローに注意すること。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Lcm<T>(PhantomData<T>);
+
+    impl<T: Integer + Unsigned> SemiGroup for Lcm<T> {
+        type Set = T;
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            lhs.lcm(&rhs)
+        }
+    }
+
+    impl<T: Integer + Unsigned> Identity for Lcm<T> {
+        fn id() -> Self::Set {
+            T::one()
+        }
+    }
+
+    impl<T: Integer + Unsigned> marker::Idempotent for Lcm<T> {}
+    impl<T: Integer + Unsigned> marker::Commutative for Lcm<T> {}
+
+    /// `(a, b)`で一次関数`x -> a x + b`を表す。
+    /// 時系列順に合成する。つまり、`op(f, g)`は`x -> g(f(x))`である。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ops::{ops::Affine, SemiGroup};
+    ///
+    /// let f = (2, 1); // x -> 2x + 1
+    /// let g = (3, 4); // x -> 3x + 4
+    /// let h = Affine::<i64>::op(f, g);
+    ///
+    /// assert_eq!(h, (6, 7));
+    /// assert_eq!(Affine::eval(h, 5), Affine::eval(g, Affine::eval(f, 5)));
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Affine<T>(PhantomData<T>);
+
+    impl<T> Affine<T>
+    where
+        T: Add<Output = T> + Mul<Output = T>,
+    {
+        /// `f(x)`を計算する。
+        pub fn eval((a, b): (T, T), x: T) -> T {
+            a * x + b
+        }
+    }
+
+    impl<T> SemiGroup for Affine<T>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T>,
+    {
+        type Set = (T, T);
+
+        fn op((a1, b1): Self::Set, (a2, b2): Self::Set) -> Self::Set {
+            (a1 * a2, b1 * a2 + b2)
+        }
+    }
+
+    impl<T> Identity for Affine<T>
+    where
+        T: Zero + One,
+        Affine<T>: SemiGroup<Set = (T, T)>,
+    {
+        fn id() -> Self::Set {
+            (T::one(), T::zero())
+        }
+    }
+
+    /// `a`が可逆な場合に限る。
+    impl<T> Inverse for Affine<T>
+    where
+        T: Field,
+        Affine<T>: Monoid<Set = (T, T)>,
+    {
+        fn inv((a, b): Self::Set) -> Self::Set {
+            let a = T::one() / a;
+            (a, -(b * a))
+        }
+    }
+
+    /// `(最小値, 最小値の個数)`
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct MinCount<T>(PhantomData<T>);
+
+    impl<T: Ord> SemiGroup for MinCount<T> {
+        type Set = (T, usize);
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            match lhs.0.cmp(&rhs.0) {
+                std::cmp::Ordering::Less => lhs,
+                std::cmp::Ordering::Equal => (lhs.0, lhs.1 + rhs.1),
+                std::cmp::Ordering::Greater => rhs,
+            }
+        }
+    }
+
+    impl<T> Identity for MinCount<T>
+    where
+        T: Ord,
+        Min<T>: Identity<Set = T>,
+    {
+        fn id() -> Self::Set {
+            (Min::<T>::id(), 0)
+        }
+    }
+
+    impl<T: Ord> marker::Commutative for MinCount<T> {}
+
+    /// 空でない連続部分列の和の最大値を計算する。単位元`None`は空列を表す。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct MaxSubarraySum<T>(PhantomData<T>);
+
+    /// [`MaxSubarraySum`]の元。各値は空でない部分列についての最大値である。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct SubarraySum<T> {
+        /// 全体の和
+        pub sum: T,
+        /// 接頭辞の和の最大値
+        pub prefix: T,
+        /// 接尾辞の和の最大値
+        pub suffix: T,
+        /// 連続部分列の和の最大値
+        pub max: T,
+    }
+
+    impl<T: Copy> SubarraySum<T> {
+        /// 長さ１の列`[x]`に対応する値を返す。
+        pub fn new(x: T) -> Option<Self> {
+            Some(Self {
+                sum: x,
+                prefix: x,
+                suffix: x,
+                max: x,
+            })
+        }
+    }
+
+    impl<T> SemiGroup for MaxSubarraySum<T>
+    where
+        T: Copy + Ord + Add<Output = T>,
+    {
+        type Set = Option<SubarraySum<T>>;
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            match (lhs, rhs) {
+                (Some(l), Some(r)) => Some(SubarraySum {
+                    sum: l.sum + r.sum,
+                    prefix: l.prefix.max(l.sum + r.prefix),
+                    suffix: r.suffix.max(l.suffix + r.sum),
+                    max: l.max.max(r.max).max(l.suffix + r.prefix),
+                }),
+                (lhs, None) => lhs,
+                (None, rhs) => rhs,
+            }
+        }
+    }
+
+    impl<T> Identity for MaxSubarraySum<T>
+    where
+        T: Copy + Ord + Add<Output = T>,
+    {
+        fn id() -> Self::Set {
+            None
+        }
+    }
+
+    /// `(和, 要素数)`。区間幅に依存する作用を扱う際に利用する。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct SumWithLength<T>(PhantomData<T>);
+
+    impl<T> SemiGroup for SumWithLength<T>
+    where
+        T: Add<Output = T>,
+    {
+        type Set = (T, usize);
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            (lhs.0 + rhs.0, lhs.1 + rhs.1)
+        }
+    }
+
+    impl<T> Identity for SumWithLength<T>
+    where
+        T: Zero,
+    {
+        fn id() -> Self::Set {
+            (T::zero(), 0)
+        }
+    }
+
+    impl<T> marker::Commutative for SumWithLength<T> where SumWithLength<T>: SemiGroup {}
+
+    /// 2x2 行列の積
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Matrix2<T>(PhantomData<T>);
+
+    impl<T> SemiGroup for Matrix2<T>
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T>,
+    {
+        type Set = [[T; 2]; 2];
+
+        fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+            let [[a, b], [c, d]] = lhs;
+            let [[e, f], [g, h]] = rhs;
+            [
+                [a * e + b * g, a * f + b * h],
+                [c * e + d * g, c * f + d * h],
+            ]
+        }
+    }
+
+    impl<T> Identity for Matrix2<T>
+    where
+        T: Zero + One,
+        Matrix2<T>: SemiGroup<Set = [[T; 2]; 2]>,
+    {
+        fn id() -> Self::Set {
+            [[T::one(), T::zero()], [T::zero(), T::one()]]
+        }
+    }
+
+    /// 通常の加法・乗法からなる半環
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Arithmetic<T>(PhantomData<T>);
+
+    impl<T> Semiring for Arithmetic<T>
+    where
+        T: Copy + Zero + One,
+    {
+        type Set = T;
+        type Add = Additive<T>;
+        type Mul = Multiplicative<T>;
+    }
+
+    /// 最小値と加法からなるトロピカル半環。`T::MAX`を無限大とみなす。
+    ///
+    /// 乗法（加算）のモノイドとしても利用できる。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct MinPlus<T>(PhantomData<T>);
+
+    /// 最大値と加法からなるトロピカル半環。`T::MIN`を負の無限大とみなす。
+    ///
+    /// 乗法（加算）のモノイドとしても利用できる。
+    /// 符号なし整数では`T::MIN`が乗法の単位元`0`と一致してしまうので、符号付き整数に限る。
+    ///
+    /// ```compile_fail
+    /// use ops::{ops::MaxPlus, Semiring};
+    ///
+    /// MaxPlus::<u32>::one();
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct MaxPlus<T>(PhantomData<T>);
+
+    macro_rules! min_plus_impl {
+        ($( $t:ty )+) => {$(
+            impl SemiGroup for MinPlus<$t> {
+                type Set = $t;
+
+                fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+                    if lhs == <$t>::MAX || rhs == <$t>::MAX {
+                        <$t>::MAX
+                    } else {
+                        lhs + rhs
+                    }
+                }
+            }
+
+            impl Identity for MinPlus<$t> {
+                fn id() -> Self::Set {
+                    0
+                }
+            }
+
+            impl marker::Commutative for MinPlus<$t> {}
+
+            impl Semiring for MinPlus<$t> {
+                type Set = $t;
+                type Add = Min<$t>;
+                type Mul = Self;
+            }
+        )+};
+    }
+    min_plus_impl!( u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize );
+
+    macro_rules! max_plus_impl {
+        ($( $t:ty )+) => {$(
+            impl SemiGroup for MaxPlus<$t> {
+                type Set = $t;
+
+                fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+                    if lhs == <$t>::MIN || rhs == <$t>::MIN {
+                        <$t>::MIN
+                    } else {
+                        lhs + rhs
+                    }
+                }
+            }
+
+            impl Identity for MaxPlus<$t> {
+                fn id() -> Self::Set {
+                    0
+                }
+            }
+
+            impl marker::Commutative for MaxPlus<$t> {}
+
+            impl Semiring for MaxPlus<$t> {
+                type Set = $t;
+                type Add = Max<$t>;
+                type Mul = Self;
+            }
+        )+};
+    }
+    max_plus_impl!( i8 i16 i32 i64 i128 isize );
+
+    /// 論理和と論理積からなる半環
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct Boolean;
+
+    impl Semiring for Boolean {
+        type Set = bool;
+        type Add = Max<bool>;
+        type Mul = Min<bool>;
+    }
+}
+
+/// 代数的構造の公理を乱択で検査する。
+///
+/// 各関数は`trials`回だけ`gen`で値を生成して検査し、反例を見つけた場合は`Err`を返す。
+///
+/// # Example
+///
+/// ```
+/// use ops::{ops::Affine, testing};
+/// use rand::Rng;
+///
+/// let mut rng = rand::rng();
+/// let gen = |rng: &mut rand::rngs::ThreadRng| (rng.random_range(-9..9), rng.random_range(-9..9));
+///
+/// assert!(testing::check_monoid::<Affine<i64>, _, _>(&mut rng, 100, gen).is_ok());
+/// assert!(testing::check_commutativity::<Affine<i64>, _, _>(&mut rng, 100, gen).is_err());
+/// ```
+#[cfg(any(test, feature = "testing"))]
+pub mod testing {
+    use std::{
+        fmt::{Debug, Display},
+        ops::{Add, Div, Mul, Neg},
+    };
+
+    use num_traits::{One, Zero};
+    use rand::Rng;
+
+    use crate::{
+        marker::{Commutative, Idempotent},
+        ops::{Additive, Multiplicative},
+        Group, Identity, Monoid, MonoidAction, MonoidActionWithSize, SemiGroup, Semiring,
+    };
+
+    /// 公理に反する具体例
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Violation<S> {
+        /// (x + y) + z != x + (y + z)
+        Associativity([S; 3]),
+        /// e + x != x または x + e != x
+        Identity(S),
+        /// inv(x) + x != e または x + inv(x) != e
+        Inverse(S),
+        /// x + y != y + x
+        Commutativity([S; 2]),
+        /// x + x != x
+        Idempotency(S),
+    }
+
+    impl<S: Debug> Display for Violation<S> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Violation::Associativity([x, y, z]) => write!(
+                    f,
+                    "associativity is violated: x = {x:?}, y = {y:?}, z = {z:?}"
+                ),
+                Violation::Identity(x) => write!(f, "identity is violated: x = {x:?}"),
+                Violation::Inverse(x) => write!(f, "inverse is violated: x = {x:?}"),
+                Violation::Commutativity([x, y]) => {
+                    write!(f, "commutativity is violated: x = {x:?}, y = {y:?}")
+                }
+                Violation::Idempotency(x) => write!(f, "idempotency is violated: x = {x:?}"),
+            }
+        }
+    }
+
+    impl<S: Debug> std::error::Error for Violation<S> {}
+
+    /// 遅延伝搬の条件に反する具体例。[`MonoidAction`]を参照。
+    /// 区間幅に依存しない作用では、`size`は常に`1`である。
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ActionViolation<F, X> {
+        /// act(f * g, x) != act(g, act(f, x))
+        Composition { f: F, g: F, x: (X, usize) },
+        /// act(e, x) != x
+        Identity { x: (X, usize) },
+        /// act(f, x + y) != act(f, x) + act(f, y)
+        Distributivity { f: F, x: (X, usize), y: (X, usize) },
+    }
+
+    impl<F: Debug, X: Debug> Display for ActionViolation<F, X> {
+        fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                ActionViolation::Composition { f, g, x } => write!(
+                    fmt,
+                    "composition law is violated: f = {f:?}, g = {g:?}, (x, size) = {x:?}"
+                ),
+                ActionViolation::Identity { x } => {
+                    write!(fmt, "identity law is violated: (x, size) = {x:?}")
+                }
+                ActionViolation::Distributivity { f, x, y } => write!(
+                    fmt,
+                    "distributive law is violated: f = {f:?}, (x, size) = {x:?}, (y, size) = {y:?}"
+                ),
+            }
+        }
+    }
+
+    impl<F: Debug, X: Debug> std::error::Error for ActionViolation<F, X> {}
+
+    /// 半環の公理に反する具体例
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum SemiringViolation<S> {
+        /// 加法が可換モノイドでない
+        Add(Violation<S>),
+        /// 乗法がモノイドでない
+        Mul(Violation<S>),
+        /// a * (b + c) != a * b + a * c または (a + b) * c != a * c + b * c
+        Distributivity([S; 3]),
+        /// 0 * a != 0 または a * 0 != 0
+        Annihilation(S),
+    }
+
+    impl<S: Debug> Display for SemiringViolation<S> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                SemiringViolation::Add(v) => write!(f, "addition: {v}"),
+                SemiringViolation::Mul(v) => write!(f, "multiplication: {v}"),
+                SemiringViolation::Distributivity([a, b, c]) => write!(
+                    f,
+                    "distributive law is violated: a = {a:?}, b = {b:?}, c = {c:?}"
+                ),
+                SemiringViolation::Annihilation(a) => {
+                    write!(f, "zero does not annihilate: a = {a:?}")
+                }
+            }
+        }
+    }
+
+    impl<S: Debug> std::error::Error for SemiringViolation<S> {}
+
+    type ActionResult<A> = Result<
+        (),
+        ActionViolation<
+            <<A as MonoidActionWithSize>::Map as SemiGroup>::Set,
+            <<A as MonoidActionWithSize>::Arg as SemiGroup>::Set,
+        >,
+    >;
+
+    /// 結合法則を検査する。
+    pub fn check_associativity<T, R, G>(
+        rng: &mut R,
+        trials: usize,
+        mut gen: G,
+    ) -> Result<(), Violation<T::Set>>
+    where
+        T: SemiGroup<Set: Clone + PartialEq>,
+        R: Rng,
+        G: FnMut(&mut R) -> T::Set,
+    {
+        for _ in 0..trials {
+            let [x, y, z] = [gen(rng), gen(rng), gen(rng)];
+            let lhs = T::op(T::op(x.clone(), y.clone()), z.clone());
+            let rhs = T::op(x.clone(), T::op(y.clone(), z.clone()));
+            if lhs != rhs {
+                return Err(Violation::Associativity([x, y, z]));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 単位元の条件を検査する。
+    pub fn check_identity<T, R, G>(
+        rng: &mut R,
+        trials: usize,
+        mut gen: G,
+    ) -> Result<(), Violation<T::Set>>
+    where
+        T: Monoid<Set: Clone + PartialEq>,
+        R: Rng,
+        G: FnMut(&mut R) -> T::Set,
+    {
+        for _ in 0..trials {
+            let x = gen(rng);
+            if T::op(T::id(), x.clone()) != x || T::op(x.clone(), T::id()) != x {
+                return Err(Violation::Identity(x));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 逆元の条件を検査する。
+    pub fn check_inverse<T, R, G>(
+        rng: &mut R,
+        trials: usize,
+        mut gen: G,
+    ) -> Result<(), Violation<T::Set>>
+    where
+        T: Group<Set: Clone + PartialEq>,
+        R: Rng,
+        G: FnMut(&mut R) -> T::Set,
+    {
+        for _ in 0..trials {
+            let x = gen(rng);
+            if T::op(T::inv(x.clone()), x.clone()) != T::id()
+                || T::op(x.clone(), T::inv(x.clone())) != T::id()
+            {
+                return Err(Violation::Inverse(x));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 交換法則を検査する。
+    pub fn check_commutativity<T, R, G>(
+        rng: &mut R,
+        trials: usize,
+        mut gen: G,
+    ) -> Result<(), Violation<T::Set>>
+    where
+        T: SemiGroup<Set: Clone + PartialEq>,
+        R: Rng,
+        G: FnMut(&mut R) -> T::Set,
+    {
+        for _ in 0..trials {
+            let [x, y] = [gen(rng), gen(rng)];
+            if T::op(x.clone(), y.clone()) != T::op(y.clone(), x.clone()) {
+                return Err(Violation::Commutativity([x, y]));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 冪等性を検査する。
+    pub fn check_idempotency<T, R, G>(
+        rng: &mut R,
+        trials: usize,
+        mut gen: G,
+    ) -> Result<(), Violation<T::Set>>
+    where
+        T: SemiGroup<Set: Clone + PartialEq>,
+        R: Rng,
+        G: FnMut(&mut R) -> T::Set,
+    {
+        for _ in 0..trials {
+            let x = gen(rng);
+            if T::op(x.clone(), x.clone()) != x {
+                return Err(Violation::Idempotency(x));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 結合法則と単位元の条件を検査する。
+    pub fn check_monoid<T, R, G>(
+        rng: &mut R,
+        trials: usize,
+        mut gen: G,
+    ) -> Result<(), Violation<T::Set>>
+    where
+        T: Monoid<Set: Clone + PartialEq>,
+        R: Rng,
+        G: FnMut(&mut R) -> T::Set,
+    {
+        check_associativity::<T, R, _>(rng, trials, &mut gen)?;
+        check_identity::<T, R, _>(rng, trials, &mut gen)
+    }
+
+    /// 群の公理を検査する。
+    pub fn check_group<T, R, G>(
+        rng: &mut R,
+        trials: usize,
+        mut gen: G,
+    ) -> Result<(), Violation<T::Set>>
+    where
+        T: Group<Set: Clone + PartialEq>,
+        R: Rng,
+        G: FnMut(&mut R) -> T::Set,
+    {
+        check_monoid::<T, R, _>(rng, trials, &mut gen)?;
+        check_inverse::<T, R, _>(rng, trials, &mut gen)
+    }
+
+    /// 数の型が体をなすか検査する。乗法逆元は`1 / x`で求める。
+    ///
+    /// 整数型のように除算が切り捨てとなる型では、乗法逆元の条件に反する。
+    pub fn check_field<T, R, G>(rng: &mut R, trials: usize, mut gen: G) -> Result<(), Violation<T>>
+    where
+        T: Clone
+            + PartialEq
+            + Zero
+            + One
+            + Add<Output = T>
+            + Mul<Output = T>
+            + Div<Output = T>
+            + Neg<Output = T>,
+        R: Rng,
+        G: FnMut(&mut R) -> T,
+    {
+        check_group::<Additive<T>, R, _>(rng, trials, &mut gen)?;
+        check_commutativity::<Additive<T>, R, _>(rng, trials, &mut gen)?;
+        check_monoid::<Multiplicative<T>, R, _>(rng, trials, &mut gen)?;
+        check_commutativity::<Multiplicative<T>, R, _>(rng, trials, &mut gen)?;
+
+        for _ in 0..trials {
+            let x = gen(rng);
+            if x.is_zero() {
+                continue;
+            }
+            if x.clone() * (T::one() / x.clone()) != T::one() {
+                return Err(Violation::Inverse(x));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 可換性マーカーが正しいか検査する。
+    pub fn check_commutative_marker<T, R, G>(
+        rng: &mut R,
+        trials: usize,
+        gen: G,
+    ) -> Result<(), Violation<T::Set>>
+    where
+        T: SemiGroup<Set: Clone + PartialEq> + Commutative,
+        R: Rng,
+        G: FnMut(&mut R) -> T::Set,
+    {
+        check_commutativity::<T, R, G>(rng, trials, gen)
+    }
+
+    /// 冪等性マーカーが正しいか検査する。
+    pub fn check_idempotent_marker<T, R, G>(
+        rng: &mut R,
+        trials: usize,
+        gen: G,
+    ) -> Result<(), Violation<T::Set>>
+    where
+        T: SemiGroup<Set: Clone + PartialEq> + Idempotent,
+        R: Rng,
+        G: FnMut(&mut R) -> T::Set,
+    {
+        check_idempotency::<T, R, G>(rng, trials, gen)
+    }
+
+    /// 半環の公理を検査する。反例は`[a, b, c]`の形で返す。
+    pub fn check_semiring<S, R, G>(
+        rng: &mut R,
+        trials: usize,
+        mut gen: G,
+    ) -> Result<(), SemiringViolation<S::Set>>
+    where
+        S: Semiring<Set: Clone + PartialEq>,
+        R: Rng,
+        G: FnMut(&mut R) -> S::Set,
+    {
+        check_monoid::<S::Add, R, _>(rng, trials, &mut gen).map_err(SemiringViolation::Add)?;
+        check_commutativity::<S::Add, R, _>(rng, trials, &mut gen)
+            .map_err(SemiringViolation::Add)?;
+        check_monoid::<S::Mul, R, _>(rng, trials, &mut gen).map_err(SemiringViolation::Mul)?;
+
+        for _ in 0..trials {
+            let [a, b, c] = [gen(rng), gen(rng), gen(rng)];
+            let lhs = S::mul(a.clone(), S::add(b.clone(), c.clone()));
+            let rhs = S::add(S::mul(a.clone(), b.clone()), S::mul(a.clone(), c.clone()));
+            if lhs != rhs {
+                return Err(SemiringViolation::Distributivity([a, b, c]));
+            }
+
+            let lhs = S::mul(S::add(a.clone(), b.clone()), c.clone());
+            let rhs = S::add(S::mul(a.clone(), c.clone()), S::mul(b.clone(), c.clone()));
+            if lhs != rhs {
+                return Err(SemiringViolation::Distributivity([a, b, c]));
+            }
+
+            if S::mul(S::zero(), a.clone()) != S::zero()
+                || S::mul(a.clone(), S::zero()) != S::zero()
+            {
+                return Err(SemiringViolation::Annihilation(a));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 遅延伝搬の３条件を検査する。
+    pub fn check_monoid_action<A, R, GF, GX>(
+        rng: &mut R,
+        trials: usize,
+        gen_map: GF,
+        mut gen_arg: GX,
+    ) -> ActionResult<A>
+    where
+        A: MonoidAction<Map: Monoid<Set: Clone + PartialEq>, Arg: Monoid<Set: Clone + PartialEq>>,
+        R: Rng,
+        GF: FnMut(&mut R) -> <A::Map as SemiGroup>::Set,
+        GX: FnMut(&mut R) -> <A::Arg as SemiGroup>::Set,
+    {
+        check_monoid_action_with_size::<A, R, _, _>(rng, trials, gen_map, |rng| (gen_arg(rng), 1))
+    }
+
+    /// 区間幅に依存する作用について、遅延伝搬の３条件を検査する。
+    /// `gen_arg`は値とそれが表す区間幅の組を生成する。
+    pub fn check_monoid_action_with_size<A, R, GF, GX>(
+        rng: &mut R,
+        trials: usize,
+        mut gen_map: GF,
+        mut gen_arg: GX,
+    ) -> ActionResult<A>
+    where
+        A: MonoidActionWithSize<
+            Map: Monoid<Set: Clone + PartialEq>,
+            Arg: Monoid<Set: Clone + PartialEq>,
+        >,
+        R: Rng,
+        GF: FnMut(&mut R) -> <A::Map as SemiGroup>::Set,
+        GX: FnMut(&mut R) -> (<A::Arg as SemiGroup>::Set, usize),
+    {
+        for _ in 0..trials {
+            let [f, g] = [gen_map(rng), gen_map(rng)];
+            let [x, y] = [gen_arg(rng), gen_arg(rng)];
+
+            let lhs = A::act_with_size(A::Map::op(f.clone(), g.clone()), x.0.clone(), x.1);
+            let rhs = A::act_with_size(
+                g.clone(),
+                A::act_with_size(f.clone(), x.0.clone(), x.1),
+                x.1,
+            );
+            if lhs != rhs {
+                return Err(ActionViolation::Composition { f, g, x });
+            }
+
+            if A::act_with_size(A::Map::id(), x.0.clone(), x.1) != x.0 {
+                return Err(ActionViolation::Identity { x });
+            }
+
+            let lhs = A::act_with_size(f.clone(), A::Arg::op(x.0.clone(), y.0.clone()), x.1 + y.1);
+            let rhs = A::Arg::op(
+                A::act_with_size(f.clone(), x.0.clone(), x.1),
+                A::act_with_size(f.clone(), y.0.clone(), y.1),
+            );
+            if lhs != rhs {
+                return Err(ActionViolation::Distributivity { f, x, y });
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use rand::{rngs::ThreadRng, Rng};
+
+        use super::*;
+        use crate::{ops::*, Dual};
+
+        const TRIALS: usize = 1000;
+
+        fn small(rng: &mut ThreadRng) -> i64 {
+            rng.random_range(-1000..1000)
+        }
+
+        fn unsigned(rng: &mut ThreadRng) -> u64 {
+            rng.random_range(0..1000)
+        }
+
+        #[test]
+        fn primitive_ops() {
+            let rng = &mut rand::rng();
+
+            check_monoid::<Min<i64>, _, _>(rng, TRIALS, small).unwrap();
+            check_commutative_marker::<Min<i64>, _, _>(rng, TRIALS, small).unwrap();
+            check_idempotent_marker::<Min<i64>, _, _>(rng, TRIALS, small).unwrap();
+
+            check_monoid::<Max<i64>, _, _>(rng, TRIALS, small).unwrap();
+            check_commutative_marker::<Max<i64>, _, _>(rng, TRIALS, small).unwrap();
+            check_idempotent_marker::<Max<i64>, _, _>(rng, TRIALS, small).unwrap();
+
+            check_group::<Additive<i64>, _, _>(rng, TRIALS, small).unwrap();
+            check_commutative_marker::<Additive<i64>, _, _>(rng, TRIALS, small).unwrap();
+
+            check_monoid::<Multiplicative<i64>, _, _>(rng, TRIALS, small).unwrap();
+            check_commutative_marker::<Multiplicative<i64>, _, _>(rng, TRIALS, small).unwrap();
+
+            for gen in [small, |rng: &mut ThreadRng| rng.random()] {
+                check_monoid::<BitAnd<i64>, _, _>(rng, TRIALS, gen).unwrap();
+                check_commutative_marker::<BitAnd<i64>, _, _>(rng, TRIALS, gen).unwrap();
+                check_idempotent_marker::<BitAnd<i64>, _, _>(rng, TRIALS, gen).unwrap();
+
+                check_monoid::<BitOr<i64>, _, _>(rng, TRIALS, gen).unwrap();
+                check_commutative_marker::<BitOr<i64>, _, _>(rng, TRIALS, gen).unwrap();
+                check_idempotent_marker::<BitOr<i64>, _, _>(rng, TRIALS, gen).unwrap();
+
+                check_group::<BitXor<i64>, _, _>(rng, TRIALS, gen).unwrap();
+                check_commutative_marker::<BitXor<i64>, _, _>(rng, TRIALS, gen).unwrap();
+            }
+
+            check_monoid::<Gcd<u64>, _, _>(rng, TRIALS, unsigned).unwrap();
+            check_commutative_marker::<Gcd<u64>, _, _>(rng, TRIALS, unsigned).unwrap();
+            check_idempotent_marker::<Gcd<u64>, _, _>(rng, TRIALS, unsigned).unwrap();
+
+            check_monoid::<Lcm<u64>, _, _>(rng, TRIALS, unsigned).unwrap();
+            check_commutative_marker::<Lcm<u64>, _, _>(rng, TRIALS, unsigned).unwrap();
+            check_idempotent_marker::<Lcm<u64>, _, _>(rng, TRIALS, unsigned).unwrap();
+        }
+
+        #[test]
+        fn compound_ops() {
+            let rng = &mut rand::rng();
+
+            let affine = |rng: &mut ThreadRng| (small(rng), small(rng));
+            check_monoid::<Affine<i64>, _, _>(rng, TRIALS, affine).unwrap();
+            assert!(check_commutativity::<Affine<i64>, _, _>(rng, TRIALS, affine).is_err());
+
+            check_monoid::<Dual<Affine<i64>>, _, _>(rng, TRIALS, affine).unwrap();
+
+            let affine = |rng: &mut ThreadRng| {
+                let [a, b] = [rng.random_range(1..8), rng.random_range(-8..8)];
+                (f64::from(1 << a), f64::from(b))
+            };
+            check_group::<Affine<f64>, _, _>(rng, TRIALS, affine).unwrap();
+
+            // 整数の除算は切り捨てなので、整数は体をなさない
+            check_field::<f64, _, _>(rng, TRIALS, |rng| f64::from(rng.random_range(-8..8)))
+                .unwrap();
+            assert!(check_field::<i64, _, _>(rng, TRIALS, small).is_err());
+
+            let min_count =
+                |rng: &mut ThreadRng| (rng.random_range(0..10), rng.random_range(1..10));
+            check_monoid::<MinCount<i64>, _, _>(rng, TRIALS, min_count).unwrap();
+            check_commutative_marker::<MinCount<i64>, _, _>(rng, TRIALS, min_count).unwrap();
+
+            let subarray = |rng: &mut ThreadRng| {
+                let n = rng.random_range(0..4);
+                (0..n)
+                    .map(|_| SubarraySum::new(small(rng)))
+                    .fold(None, MaxSubarraySum::op)
+            };
+            check_monoid::<MaxSubarraySum<i64>, _, _>(rng, TRIALS, subarray).unwrap();
+
+            let sum_len = |rng: &mut ThreadRng| (small(rng), rng.random_range(0..10));
+            check_monoid::<SumWithLength<i64>, _, _>(rng, TRIALS, sum_len).unwrap();
+            check_commutative_marker::<SumWithLength<i64>, _, _>(rng, TRIALS, sum_len).unwrap();
+
+            let matrix = |rng: &mut ThreadRng| [[small(rng), small(rng)], [small(rng), small(rng)]];
+            check_monoid::<Matrix2<i64>, _, _>(rng, TRIALS, matrix).unwrap();
+            assert!(check_commutativity::<Matrix2<i64>, _, _>(rng, TRIALS, matrix).is_err());
+        }
+
+        #[test]
+        fn semiring() {
+            let rng = &mut rand::rng();
+
+            check_semiring::<Arithmetic<i64>, _, _>(rng, TRIALS, small).unwrap();
+
+            let tropical = |rng: &mut ThreadRng| match rng.random_range(0..10) {
+                0 => i64::MAX,
+                1 => i64::MIN,
+                _ => small(rng),
+            };
+            check_semiring::<MinPlus<i64>, _, _>(rng, TRIALS, |rng| tropical(rng).max(-1000))
+                .unwrap();
+            check_semiring::<MaxPlus<i64>, _, _>(rng, TRIALS, |rng| tropical(rng).min(1000))
+                .unwrap();
+
+            // 符号なし整数では MinPlus のみ定義される
+            let unsigned_tropical = |rng: &mut ThreadRng| match rng.random_range(0..10) {
+                0 => u32::MAX,
+                _ => rng.random_range(0..1000),
+            };
+            check_semiring::<MinPlus<u32>, _, _>(rng, TRIALS, unsigned_tropical).unwrap();
+            assert_eq!(MinPlus::<u32>::mul(MinPlus::<u32>::one(), 5), 5);
+
+            let small_tropical = |rng: &mut ThreadRng| match rng.random_range(0..10) {
+                0 => i32::MIN,
+                _ => rng.random_range(-1000..1000),
+            };
+            check_semiring::<MaxPlus<i32>, _, _>(rng, TRIALS, small_tropical).unwrap();
+            assert_eq!(MaxPlus::<i32>::mul(MaxPlus::<i32>::one(), 5), 5);
+            assert_ne!(MaxPlus::<i32>::zero(), MaxPlus::<i32>::one());
+
+            check_semiring::<Boolean, _, _>(rng, TRIALS, |rng| rng.random()).unwrap();
+        }
+
+        #[derive(Debug, Clone, PartialEq, ops_derive::Monoid)]
+        #[op(crate = crate)]
+        struct MinSum {
+            #[op(Min)]
+            min: i64,
+            #[op(Additive)]
+            sum: i64,
+        }
+
+        #[derive(Debug, Clone, PartialEq, ops_derive::Monoid)]
+        #[op(crate = crate)]
+        struct SumXor<T>(#[op(Additive)] T, #[op(BitXor)] T);
+
+        #[derive(Debug, Clone, PartialEq, ops_derive::Monoid)]
+        #[op(crate = crate)]
+        struct AffineMax {
+            #[op(Affine<i64>)]
+            affine: (i64, i64),
+            #[op(Max)]
+            max: i64,
+        }
+
+        #[test]
+        fn derive() {
+            let rng = &mut rand::rng();
+
+            let gen = |rng: &mut ThreadRng| MinSum {
+                min: small(rng),
+                sum: small(rng),
+            };
+            check_monoid::<MinSum, _, _>(rng, TRIALS, gen).unwrap();
+            check_commutative_marker::<MinSum, _, _>(rng, TRIALS, gen).unwrap();
+
+            check_group::<SumXor<i64>, _, _>(rng, TRIALS, |rng| SumXor(small(rng), small(rng)))
+                .unwrap();
+            check_commutative_marker::<SumXor<i64>, _, _>(rng, TRIALS, |rng| {
+                SumXor(small(rng), small(rng))
+            })
+            .unwrap();
+
+            let gen = |rng: &mut ThreadRng| AffineMax {
+                affine: (small(rng), small(rng)),
+                max: small(rng),
+            };
+            check_monoid::<AffineMax, _, _>(rng, TRIALS, gen).unwrap();
+            assert!(check_commutativity::<AffineMax, _, _>(rng, TRIALS, gen).is_err());
+        }
+
+        /// 区間アフィン変換・区間和
+        struct AffineSum;
+
+        impl MonoidAction for AffineSum {
+            type Map = Affine<i64>;
+            type Arg = SumWithLength<i64>;
+
+            fn act((a, b): (i64, i64), (sum, len): (i64, usize)) -> (i64, usize) {
+                (a * sum + b * len as i64, len)
+            }
+        }
+
+        /// 区間加算・区間和（区間幅を無視した誤った実装）
+        struct WrongAddSum;
+
+        impl MonoidAction for WrongAddSum {
+            type Map = Additive<i64>;
+            type Arg = Additive<i64>;
+
+            fn act(f: i64, x: i64) -> i64 {
+                f + x
+            }
+        }
+
+        #[test]
+        fn monoid_action() {
+            let rng = &mut rand::rng();
+
+            check_monoid_action::<AffineSum, _, _, _>(
+                rng,
+                TRIALS,
+                |rng| (small(rng), small(rng)),
+                |rng| (small(rng), rng.random_range(0..10)),
+            )
+            .unwrap();
+
+            let err = check_monoid_action::<WrongAddSum, _, _, _>(rng, TRIALS, small, small);
+            assert!(matches!(err, Err(ActionViolation::Distributivity { .. })));
+        }
+    }
 }
@@ -0,0 +1,200 @@
+//! 構造体の各フィールドに演算を指定して、成分ごとの演算を導出する。
+//!
+//! ```
+//! use ops::{ops::{Additive, Min}, Identity, SemiGroup};
+//! use ops_derive::Monoid;
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq, Monoid)]
+//! struct Node {
+//!     #[op(Min)]
+//!     min: i64,
+//!     #[op(Additive)]
+//!     sum: i64,
+//! }
+//!
+//! let a = Node { min: 3, sum: 3 };
+//! let b = Node { min: 1, sum: 1 };
+//! assert_eq!(Node::op(a, b), Node { min: 1, sum: 4 });
+//! assert_eq!(Node::op(a, Node::id()), a);
+//! ```
+//!
+//! 構造体自身が演算を表す型となり、`Set = Self`である。
+//! `SemiGroup`に加えて、すべてのフィールドの演算が満たす`Identity`・`Inverse`・`Commutative`・`Idempotent`を実装する。
+//!
+//! # 演算の指定
+//!
+//! `#[op(Min)]`のように型引数を省略すると、フィールドの型を補って`Min<i64>`とする。
+//! `#[op(MinCount<i64>)]`のように型引数を明示した場合はそのまま利用する。
+//!
+//! # パス
+//!
+//! 生成されるコードは`ops::SemiGroup`のように相対パスで`ops`を参照する。
+//! `cargo equip`でバンドルすると`ops`はクレートルートから見える位置に置かれるので、そのまま動作する。
+//! 別のパスを参照したい場合は`#[op(crate = path::to::ops)]`を構造体に付ける。
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, Member, Path, PathArguments,
+    Result, Type, WherePredicate,
+};
+
+#[proc_macro_derive(Monoid, attributes(op))]
+pub fn derive_monoid(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct Component {
+    member: Member,
+    ty: Type,
+    op: Type,
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let krate = parse_crate_path(&input)?;
+    let components = parse_components(&input)?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // 各演算の集合がフィールドの型と一致することを要求する
+    let mut base: Vec<WherePredicate> = where_clause
+        .map(|w| w.predicates.iter().cloned().collect())
+        .unwrap_or_default();
+    base.extend(
+        components
+            .iter()
+            .map(|Component { ty, op, .. }| -> WherePredicate {
+                parse_quote!(#op: #krate::SemiGroup<Set = #ty>)
+            }),
+    );
+
+    // 演算の性質は満たすときだけ実装したい。
+    // 具体型に対する境界は満たされないとエラーになるので、高階境界にして評価を遅らせる。
+    let bounded = |bound: TokenStream| -> Vec<WherePredicate> {
+        let mut predicates = base.clone();
+        predicates.extend(
+            components
+                .iter()
+                .map(|Component { op, .. }| -> WherePredicate {
+                    parse_quote!(for<'__op> #op: #bound)
+                }),
+        );
+        predicates
+    };
+
+    let members: Vec<_> = components.iter().map(|c| &c.member).collect();
+    let ops: Vec<_> = components.iter().map(|c| &c.op).collect();
+
+    let identity = bounded(quote!(#krate::Identity));
+    let inverse = bounded(quote!(#krate::Inverse));
+    let commutative = bounded(quote!(#krate::marker::Commutative));
+    let idempotent = bounded(quote!(#krate::marker::Idempotent));
+
+    Ok(quote! {
+        impl #impl_generics #krate::SemiGroup for #name #ty_generics where #(#base,)* {
+            type Set = Self;
+
+            #[inline]
+            fn op(lhs: Self::Set, rhs: Self::Set) -> Self::Set {
+                Self {
+                    #( #members: <#ops as #krate::SemiGroup>::op(lhs.#members, rhs.#members), )*
+                }
+            }
+        }
+
+        impl #impl_generics #krate::Identity for #name #ty_generics where #(#identity,)* {
+            #[inline]
+            fn id() -> Self::Set {
+                Self {
+                    #( #members: <#ops as #krate::Identity>::id(), )*
+                }
+            }
+        }
+
+        impl #impl_generics #krate::Inverse for #name #ty_generics where #(#inverse,)* {
+            #[inline]
+            fn inv(x: Self::Set) -> Self::Set {
+                Self {
+                    #( #members: <#ops as #krate::Inverse>::inv(x.#members), )*
+                }
+            }
+        }
+
+        impl #impl_generics #krate::marker::Commutative for #name #ty_generics
+            where #(#commutative,)* {}
+
+        impl #impl_generics #krate::marker::Idempotent for #name #ty_generics
+            where #(#idempotent,)* {}
+    })
+}
+
+/// `#[op(crate = path)]`を読む。
+fn parse_crate_path(input: &DeriveInput) -> Result<Path> {
+    let mut krate = parse_quote!(ops);
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("op")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                krate = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `crate = path`"))
+            }
+        })?;
+    }
+
+    Ok(krate)
+}
+
+/// 各フィールドの`#[op(Type)]`を読む。
+fn parse_components(input: &DeriveInput) -> Result<Vec<Component>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`Monoid` can only be derived for structs",
+        ));
+    };
+
+    if let Fields::Unit = data.fields {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`Monoid` cannot be derived for unit structs",
+        ));
+    }
+
+    let mut components = Vec::with_capacity(data.fields.len());
+    for (i, field) in data.fields.iter().enumerate() {
+        let mut attrs = field.attrs.iter().filter(|attr| attr.path().is_ident("op"));
+        let (Some(attr), None) = (attrs.next(), attrs.next()) else {
+            return Err(Error::new_spanned(
+                field,
+                "each field requires exactly one `#[op(..)]` attribute",
+            ));
+        };
+
+        let mut op: Type = attr.parse_args()?;
+        // 型引数が省略されていればフィールドの型を補う
+        if let Type::Path(path) = &mut op {
+            let last = path.path.segments.last_mut().unwrap();
+            if path.qself.is_none() && last.arguments.is_none() {
+                let ty = &field.ty;
+                last.arguments = PathArguments::AngleBracketed(parse_quote!(<#ty>));
+            }
+        }
+
+        components.push(Component {
+            member: field
+                .ident
+                .clone()
+                .map_or_else(|| Member::from(i), Member::Named),
+            ty: field.ty.clone(),
+            op,
+        });
+    }
+
+    Ok(components)
+}